@@ -0,0 +1,371 @@
+use crate::TypedString;
+use crate::__tuplez::{Tuple, Unit};
+use core::{ops::Deref, pin::Pin};
+
+#[cfg(feature = "alloc")]
+use alloc::borrow::ToOwned;
+
+/// Conversions between anonymous structs with the same fields, implemented for every anonymous struct.
+///
+/// ```
+/// use structz::*;
+///
+/// let mut person = stru! {
+///     name: String::from("John"),
+///     age: 26,
+///     tags: vec!["developer"],
+/// };
+///
+/// let ref_person = person.fields_ref();
+/// assert_eq!(ref_person.cloned_fields(), person);
+///
+/// let mut_person = person.fields_mut();
+/// field!(mut_person.tags).push("rustacean");
+/// assert_eq!(field!(&person.tags), &vec!["developer", "rustacean"]);
+/// ```
+pub trait AnonymousStruct {
+    /// The type of struct whose fields are immutable references to the fields of the struct.
+    type AsRefOutput<'a>
+    where
+        Self: 'a;
+
+    /// The type of struct whose fields are mutable references to the fields of the struct.
+    type AsMutOutput<'a>
+    where
+        Self: 'a;
+
+    /// The type of struct whose fields are pinned mutable references to the fields of the struct.
+    type AsPinMutOutput<'a>
+    where
+        Self: 'a;
+
+    /// Obtain a new struct, each field is an immutable reference to the corresponding field of the struct.
+    ///
+    /// The method form of [`as_ref!`](crate::as_ref!).
+    fn fields_ref(&self) -> Self::AsRefOutput<'_>;
+
+    /// Obtain a new struct, each field is a mutable reference to the corresponding field of the struct.
+    ///
+    /// The method form of [`as_mut!`](crate::as_mut!).
+    fn fields_mut(&mut self) -> Self::AsMutOutput<'_>;
+
+    /// Project a pinned mutable reference of the struct to a new struct, each field is a pinned mutable reference
+    /// to the corresponding field of the struct.
+    ///
+    /// The method form of [`as_pin_mut!`](crate::as_pin_mut!).
+    fn fields_pin_mut(self: Pin<&mut Self>) -> Self::AsPinMutOutput<'_>;
+
+    /// If the fields of the struct are all references, clone the values they refer to into a new struct.
+    ///
+    /// ```
+    /// use structz::*;
+    ///
+    /// let name = String::from("John");
+    /// let mut age = 26;
+    /// let person = stru! { name: &name, age: &mut age };
+    /// assert_eq!(person.cloned_fields(), stru! { name: String::from("John"), age: 26 });
+    /// ```
+    fn cloned_fields(&self) -> <Self as ClonedFields>::Output
+    where
+        Self: ClonedFields,
+    {
+        ClonedFields::__cloned_fields(self)
+    }
+
+    /// If the fields of the struct are all references, copy the values they refer to into a new struct.
+    ///
+    /// ```
+    /// use structz::*;
+    ///
+    /// let person = stru! { id: &1006, grade: &'A' };
+    /// assert_eq!(person.copied_fields(), stru! { id: 1006, grade: 'A' });
+    /// ```
+    fn copied_fields(&self) -> <Self as CopiedFields>::Output
+    where
+        Self: CopiedFields,
+    {
+        CopiedFields::__copied_fields(self)
+    }
+
+    /// If the fields of the struct are all references, convert the values they refer to into owned values
+    /// through [`ToOwned`](alloc::borrow::ToOwned) (requires `alloc` feature).
+    ///
+    /// Much like [`cloned_fields()`](AnonymousStruct::cloned_fields()), but can work on types like `&str` or slices.
+    ///
+    /// ```
+    /// use structz::*;
+    ///
+    /// let person = stru! { name: "John", tags: &["developer", "rustacean"][..] };
+    /// assert_eq!(
+    ///     person.to_owned_fields(),
+    ///     stru! { name: String::from("John"), tags: vec!["developer", "rustacean"] },
+    /// );
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn to_owned_fields(&self) -> <Self as OwnedFields>::Output
+    where
+        Self: OwnedFields,
+    {
+        OwnedFields::__owned_fields(self)
+    }
+
+    /// If the fields of the struct all implement [`Deref`], obtain a new struct, each field of which
+    /// is an immutable reference to the dereferenced target of the corresponding field of the struct.
+    ///
+    /// ```
+    /// use structz::*;
+    ///
+    /// let person = stru! {
+    ///     name: String::from("John"),
+    ///     tags: vec!["developer", "rustacean"],
+    /// };
+    /// let _: stru_t! { name: &str, tags: &[&str] } = person.deref_fields();
+    /// ```
+    fn deref_fields(&self) -> <Self as DerefFields>::Output<'_>
+    where
+        Self: DerefFields,
+    {
+        DerefFields::__deref_fields(self)
+    }
+}
+
+impl AnonymousStruct for Unit {
+    type AsRefOutput<'a> = Unit;
+
+    type AsMutOutput<'a> = Unit;
+
+    type AsPinMutOutput<'a> = Unit;
+
+    fn fields_ref(&self) -> Self::AsRefOutput<'_> {
+        Unit
+    }
+
+    fn fields_mut(&mut self) -> Self::AsMutOutput<'_> {
+        Unit
+    }
+
+    fn fields_pin_mut(self: Pin<&mut Self>) -> Self::AsPinMutOutput<'_> {
+        Unit
+    }
+}
+
+impl<Field, T, Other> AnonymousStruct for Tuple<(Field, T), Other>
+where
+    Field: TypedString + Copy,
+    Other: AnonymousStruct,
+{
+    type AsRefOutput<'a>
+        = Tuple<(Field, &'a T), Other::AsRefOutput<'a>>
+    where
+        Self: 'a;
+
+    type AsMutOutput<'a>
+        = Tuple<(Field, &'a mut T), Other::AsMutOutput<'a>>
+    where
+        Self: 'a;
+
+    type AsPinMutOutput<'a>
+        = Tuple<(Field, Pin<&'a mut T>), Other::AsPinMutOutput<'a>>
+    where
+        Self: 'a;
+
+    fn fields_ref(&self) -> Self::AsRefOutput<'_> {
+        Tuple((self.0 .0, &self.0 .1), self.1.fields_ref())
+    }
+
+    fn fields_mut(&mut self) -> Self::AsMutOutput<'_> {
+        Tuple((self.0 .0, &mut self.0 .1), self.1.fields_mut())
+    }
+
+    fn fields_pin_mut(self: Pin<&mut Self>) -> Self::AsPinMutOutput<'_> {
+        // SAFETY: Anonymous structs never move their fields out of a pinned reference,
+        // do not implement `Drop`, and are `Unpin` only if all fields are `Unpin`,
+        // so pinning is structural for every field.
+        unsafe {
+            let this = self.get_unchecked_mut();
+            Tuple(
+                (this.0 .0, Pin::new_unchecked(&mut this.0 .1)),
+                Pin::new_unchecked(&mut this.1).fields_pin_mut(),
+            )
+        }
+    }
+}
+
+/// A trait for anonymous structs whose fields are all references to values that implement `Clone`.
+///
+/// See [`AnonymousStruct::cloned_fields()`].
+pub trait ClonedFields {
+    /// The type of struct that carries the cloned values.
+    type Output;
+
+    #[doc(hidden)]
+    fn __cloned_fields(&self) -> Self::Output;
+}
+
+impl ClonedFields for Unit {
+    type Output = Unit;
+
+    fn __cloned_fields(&self) -> Self::Output {
+        Unit
+    }
+}
+
+impl<Field, T, Other> ClonedFields for Tuple<(Field, &T), Other>
+where
+    Field: TypedString + Copy,
+    T: Clone,
+    Other: ClonedFields,
+{
+    type Output = Tuple<(Field, T), Other::Output>;
+
+    fn __cloned_fields(&self) -> Self::Output {
+        Tuple((self.0 .0, self.0 .1.clone()), self.1.__cloned_fields())
+    }
+}
+
+impl<Field, T, Other> ClonedFields for Tuple<(Field, &mut T), Other>
+where
+    Field: TypedString + Copy,
+    T: Clone,
+    Other: ClonedFields,
+{
+    type Output = Tuple<(Field, T), Other::Output>;
+
+    fn __cloned_fields(&self) -> Self::Output {
+        Tuple((self.0 .0, self.0 .1.clone()), self.1.__cloned_fields())
+    }
+}
+
+/// A trait for anonymous structs whose fields are all references to values that implement `Copy`.
+///
+/// See [`AnonymousStruct::copied_fields()`].
+pub trait CopiedFields {
+    /// The type of struct that carries the copied values.
+    type Output;
+
+    #[doc(hidden)]
+    fn __copied_fields(&self) -> Self::Output;
+}
+
+impl CopiedFields for Unit {
+    type Output = Unit;
+
+    fn __copied_fields(&self) -> Self::Output {
+        Unit
+    }
+}
+
+impl<Field, T, Other> CopiedFields for Tuple<(Field, &T), Other>
+where
+    Field: TypedString + Copy,
+    T: Copy,
+    Other: CopiedFields,
+{
+    type Output = Tuple<(Field, T), Other::Output>;
+
+    fn __copied_fields(&self) -> Self::Output {
+        Tuple((self.0 .0, *self.0 .1), self.1.__copied_fields())
+    }
+}
+
+impl<Field, T, Other> CopiedFields for Tuple<(Field, &mut T), Other>
+where
+    Field: TypedString + Copy,
+    T: Copy,
+    Other: CopiedFields,
+{
+    type Output = Tuple<(Field, T), Other::Output>;
+
+    fn __copied_fields(&self) -> Self::Output {
+        Tuple((self.0 .0, *self.0 .1), self.1.__copied_fields())
+    }
+}
+
+/// A trait for anonymous structs whose fields are all references to values that implement `ToOwned`
+/// (requires `alloc` feature).
+///
+/// See [`AnonymousStruct::to_owned_fields()`].
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub trait OwnedFields {
+    /// The type of struct that carries the owned values.
+    type Output;
+
+    #[doc(hidden)]
+    fn __owned_fields(&self) -> Self::Output;
+}
+
+#[cfg(feature = "alloc")]
+impl OwnedFields for Unit {
+    type Output = Unit;
+
+    fn __owned_fields(&self) -> Self::Output {
+        Unit
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<Field, T, Other> OwnedFields for Tuple<(Field, &T), Other>
+where
+    Field: TypedString + Copy,
+    T: ToOwned + ?Sized,
+    Other: OwnedFields,
+{
+    type Output = Tuple<(Field, T::Owned), Other::Output>;
+
+    fn __owned_fields(&self) -> Self::Output {
+        Tuple((self.0 .0, self.0 .1.to_owned()), self.1.__owned_fields())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<Field, T, Other> OwnedFields for Tuple<(Field, &mut T), Other>
+where
+    Field: TypedString + Copy,
+    T: ToOwned + ?Sized,
+    Other: OwnedFields,
+{
+    type Output = Tuple<(Field, T::Owned), Other::Output>;
+
+    fn __owned_fields(&self) -> Self::Output {
+        Tuple((self.0 .0, self.0 .1.to_owned()), self.1.__owned_fields())
+    }
+}
+
+/// A trait for anonymous structs whose fields all implement [`Deref`].
+///
+/// See [`AnonymousStruct::deref_fields()`].
+pub trait DerefFields {
+    /// The type of struct whose fields are immutable references to the dereferenced targets.
+    type Output<'a>
+    where
+        Self: 'a;
+
+    #[doc(hidden)]
+    fn __deref_fields(&self) -> Self::Output<'_>;
+}
+
+impl DerefFields for Unit {
+    type Output<'a> = Unit;
+
+    fn __deref_fields(&self) -> Self::Output<'_> {
+        Unit
+    }
+}
+
+impl<Field, T, Other> DerefFields for Tuple<(Field, T), Other>
+where
+    Field: TypedString + Copy,
+    T: Deref,
+    Other: DerefFields,
+{
+    type Output<'a>
+        = Tuple<(Field, &'a T::Target), Other::Output<'a>>
+    where
+        Self: 'a;
+
+    fn __deref_fields(&self) -> Self::Output<'_> {
+        Tuple((self.0 .0, &*self.0 .1), self.1.__deref_fields())
+    }
+}
@@ -0,0 +1,79 @@
+use crate::TypedString;
+use crate::__tuplez::{
+    search::{Complete, Search, Used},
+    Tuple, Unit,
+};
+
+/// A trait for anonymous structs that can be built from the same fields in any order.
+///
+/// Anonymous structs built by [`stru!`] and [`stru_t!`] have their fields sorted, but tuples of
+/// `(name, value)` pairs assembled in generic code are usually not. This trait moves such fields
+/// into the canonical anonymous struct type.
+///
+/// It is not a type operator: it reorders the fields into a target type that must be known,
+/// either named or inferred from the context. Computing the canonical type from the unsorted type
+/// would select types by comparing typed strings, which stable Rust can only do in constant evaluation
+/// (see [`StrCmp`](stringz::StrCmp)).
+///
+/// The fields are only reordered, every field of the target type must be given. To leave out
+/// optional fields, see [`FillOptional`](crate::FillOptional).
+///
+/// # Generic parameters
+///
+/// * `Fields`: The tuple of `(name, value)` pairs in any order.
+/// * `I`: Type used to indicate the positions of the fields in `Fields`.
+///   Usually automatically inferred by Rust.
+///
+/// # Example
+///
+/// See [`canonicalize()`].
+pub trait Canonicalize<Fields, I> {
+    /// Build the anonymous struct from the same fields in any order.
+    fn canonicalize(fields: Fields) -> Self;
+}
+
+impl Canonicalize<Unit, Complete> for Unit {
+    fn canonicalize(_: Unit) -> Self {
+        Unit
+    }
+}
+
+impl<Field, T, Other, Fields, R, I> Canonicalize<Fields, Used<(R, I)>> for Tuple<(Field, T), Other>
+where
+    Field: TypedString,
+    Fields: Search<(Field, T), R>,
+    Other: Canonicalize<Fields::TakeRemainder, I>,
+{
+    fn canonicalize(fields: Fields) -> Self {
+        let (first, remainder) = Search::take(fields);
+        Tuple(first, Other::canonicalize(remainder))
+    }
+}
+
+/// Re-sort a tuple of `(name, value)` pairs into the canonical anonymous struct type, which is
+/// the same type that [`stru!`] produces for these fields.
+///
+/// The canonical type is usually inferred, see [`Canonicalize`] for why it cannot be computed.
+///
+/// ```
+/// use structz::*;
+/// use tuplez::TupleLike;
+///
+/// // Pushing a field breaks the order of the fields.
+/// fn with_id<S, T, I>(s: S, id: u32) -> T
+/// where
+///     S: TupleLike,
+///     T: Canonicalize<S::PushFrontOutput<(ident!(id), u32)>, I>,
+/// {
+///     canonicalize(s.push_front((<ident!(id)>::default(), id)))
+/// }
+///
+/// let user: stru_t! { id: u32, name: &'static str } = with_id(stru! { name: "John" }, 1);
+/// assert_eq!(user, stru! { name: "John", id: 1 });
+/// ```
+pub fn canonicalize<S, Fields, I>(fields: Fields) -> S
+where
+    S: Canonicalize<Fields, I>,
+{
+    S::canonicalize(fields)
+}
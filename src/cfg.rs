@@ -0,0 +1,46 @@
+use crate::__tuplez::Tuple;
+
+/// Helper trait used for [`stru_t!`] and [`named_args`](macro@crate::named_args) macros,
+/// which prepends a conditional field to the fields `R`.
+///
+/// The field is the parameter of the function pointer type, which is stripped together with
+/// its `#[cfg]` attribute if the predicate does not hold.
+#[doc(hidden)]
+pub trait __CfgField<R> {
+    type Output;
+}
+
+impl<R> __CfgField<R> for fn() {
+    type Output = R;
+}
+
+impl<F, R> __CfgField<R> for fn(F) {
+    type Output = Tuple<F, R>;
+}
+
+/// Helper trait used for [`stru!`] macro, which prepends a conditional field to the fields `R`.
+///
+/// The field is the element of the tuple, which is stripped together with its `#[cfg]` attribute
+/// if the predicate does not hold.
+#[doc(hidden)]
+pub trait __CfgValue<R> {
+    type Output;
+
+    fn insert(self, rest: R) -> Self::Output;
+}
+
+impl<R> __CfgValue<R> for () {
+    type Output = R;
+
+    fn insert(self, rest: R) -> Self::Output {
+        rest
+    }
+}
+
+impl<F, R> __CfgValue<R> for (F,) {
+    type Output = Tuple<F, R>;
+
+    fn insert(self, rest: R) -> Self::Output {
+        Tuple(self.0, rest)
+    }
+}
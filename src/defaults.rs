@@ -0,0 +1,130 @@
+use crate::TypedString;
+use crate::__tuplez::{
+    search::{Complete, Subseq, Unused, Used},
+    Tuple, TupleLike, Unit,
+};
+
+/// A trait for anonymous structs that can be built from some of their fields,
+/// with the remaining fields filled with `Default::default()`.
+///
+/// Default values declared by [`stru_t!`] are not taken, use [`DeclaredDefaults`] or
+/// [`stru_default!`] for them.
+///
+/// # Generic parameters
+///
+/// * `Given`: The anonymous struct consisting of the given fields.
+/// * `I`: Type used to indicate the positions of the given fields in the struct.
+///   Usually automatically inferred by Rust.
+///
+/// # Example
+///
+/// ```
+/// use structz::*;
+///
+/// type Options = stru_t! {
+///     port: u16,
+///     host: String,
+///     verbose: bool,
+/// };
+///
+/// let options: Options = FillDefault::fill_default(stru! {
+///     host: "localhost".to_string(),
+/// });
+/// assert_eq!(options, stru! { host: "localhost".to_string(), port: 0, verbose: false });
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "cannot build the anonymous struct from the given fields",
+    label = "a given field is unknown, or has a different type"
+)]
+pub trait FillDefault<Given, I> {
+    /// Build the anonymous struct from the given fields and the default values of the
+    /// remaining fields.
+    fn fill_default(given: Given) -> Self;
+}
+
+impl FillDefault<Unit, Complete> for Unit {
+    fn fill_default(_: Unit) -> Self {
+        Unit
+    }
+}
+
+impl<First, Other, GivenOther, I> FillDefault<Tuple<First, GivenOther>, Used<I>>
+    for Tuple<First, Other>
+where
+    Other: FillDefault<GivenOther, I>,
+{
+    fn fill_default(given: Tuple<First, GivenOther>) -> Self {
+        Tuple(given.0, Other::fill_default(given.1))
+    }
+}
+
+impl<Field, T, Other, Given, I> FillDefault<Given, Unused<I>> for Tuple<(Field, T), Other>
+where
+    Field: TypedString + Default,
+    T: Default,
+    Other: FillDefault<Given, I>,
+{
+    fn fill_default(given: Given) -> Self {
+        Tuple(Default::default(), Other::fill_default(given))
+    }
+}
+
+/// A trait for anonymous struct types declared with default values of fields by [`stru_t!`].
+///
+/// You usually do not implement it yourself: declaring default values through [`stru_t!`]
+/// implements it for the declared type, which is then used by [`stru_default!`].
+/// Fields without declared default values take `Default::default()`.
+///
+/// The default values are registered for the anonymous struct type, not for a type alias of it.
+/// Declaring other default values for the same fields makes them ambiguous, and [`stru_default!`]
+/// fails the compilation for these fields.
+///
+/// # Generic parameters
+///
+/// * `M`: Type used to identify the declaration. Usually automatically inferred by Rust.
+///
+/// # Example
+///
+/// ```
+/// use structz::*;
+///
+/// type Options = stru_t! {
+///     port: u16 = 8080,
+///     host: String,
+/// };
+///
+/// assert_eq!(Options::defaults(), stru! { host: String::new(), port: 8080 });
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "the anonymous struct type is not declared with default values",
+    label = "no default values declared for this type",
+    note = "declare default values by `stru_t! {{ name: Type = value, .. }}`, \
+            or use `FillDefault` to fill the remaining fields with `Default::default()`"
+)]
+pub trait DeclaredDefaults<M>: Sized {
+    /// Build the anonymous struct of which all fields take their default values.
+    fn defaults() -> Self;
+
+    /// Build the anonymous struct from the given fields and the default values of the
+    /// remaining fields.
+    fn fill_defaults<Given, I>(given: Given) -> Self
+    where
+        Given: TupleLike,
+        Self: Subseq<Given, I>,
+    {
+        let mut s = Self::defaults();
+        Subseq::replace_subseq(&mut s, given);
+        s
+    }
+}
+
+/// Helper trait used for [`stru_t!`] macro, which declares items in the constant argument
+/// of a type that is the same as `Self`.
+#[doc(hidden)]
+pub trait __WithItems<const N: usize> {
+    type Output;
+}
+
+impl<T, const N: usize> __WithItems<N> for T {
+    type Output = T;
+}
@@ -0,0 +1,122 @@
+use crate::TypedString;
+use crate::__tuplez::{Tuple, Unit};
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::{self, Debug, Display};
+
+/// A field that holds different values in two anonymous structs of the same type.
+///
+/// Produced by [`diff()`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldDiff {
+    /// Name of the field.
+    pub name: String,
+    /// The `Debug` representation of the field's value in the old struct.
+    pub old: String,
+    /// The `Debug` representation of the field's value in the new struct.
+    pub new: String,
+}
+
+impl Display for FieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} => {}", self.name, self.old, self.new)
+    }
+}
+
+/// A trait for anonymous structs whose fields can be compared one by one.
+///
+/// It is implemented for all anonymous structs whose field types implement
+/// both `Debug` and `PartialEq`.
+pub trait Diff {
+    /// Append the fields that hold different values in `self` and `other` to `diffs`.
+    fn diff_into(&self, other: &Self, diffs: &mut Vec<FieldDiff>);
+}
+
+impl Diff for Unit {
+    fn diff_into(&self, _: &Self, _: &mut Vec<FieldDiff>) {}
+}
+
+impl<Field, T, Other> Diff for Tuple<(Field, T), Other>
+where
+    Field: TypedString,
+    T: Debug + PartialEq,
+    Other: Diff,
+{
+    fn diff_into(&self, other: &Self, diffs: &mut Vec<FieldDiff>) {
+        if self.0 .1 != other.0 .1 {
+            diffs.push(FieldDiff {
+                name: Field::value(),
+                old: format!("{:?}", self.0 .1),
+                new: format!("{:?}", other.0 .1),
+            });
+        }
+        self.1.diff_into(&other.1, diffs);
+    }
+}
+
+/// Compare two anonymous structs of the same type field by field.
+///
+/// Returns the differing fields in the order they are stored, that is, sorted by their names.
+///
+/// ```
+/// use structz::*;
+///
+/// let old = stru! {
+///     name: "John",
+///     age: 26,
+///     tags: vec!["developer"],
+/// };
+/// let new = stru! {
+///     name: "John",
+///     age: 27,
+///     tags: vec!["developer", "rustacean"],
+/// };
+///
+/// let diffs = diff(&old, &new);
+/// assert_eq!(diffs.len(), 2);
+/// assert_eq!(diffs[0].name, "age");
+/// assert_eq!(diffs[0].old, "26");
+/// assert_eq!(diffs[0].new, "27");
+/// assert_eq!(diffs[1].name, "tags");
+/// assert_eq!(diffs[1].to_string(), r#"tags: ["developer"] => ["developer", "rustacean"]"#);
+///
+/// assert!(diff(&old, &old).is_empty());
+/// ```
+pub fn diff<S>(old: &S, new: &S) -> Vec<FieldDiff>
+where
+    S: Diff,
+{
+    let mut diffs = Vec::new();
+    old.diff_into(new, &mut diffs);
+    diffs
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_stru_eq_failed(diffs: &[FieldDiff], args: Option<fmt::Arguments<'_>>) -> ! {
+    struct Report<'a>(&'a [FieldDiff]);
+
+    impl Display for Report<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            for diff in self.0 {
+                write!(
+                    f,
+                    "\n  {}:\n     left: {}\n    right: {}",
+                    diff.name, diff.old, diff.new
+                )?;
+            }
+            Ok(())
+        }
+    }
+
+    match args {
+        Some(args) => panic!(
+            "assertion `left == right` failed: {}\ndiffering fields:{}",
+            args,
+            Report(diffs)
+        ),
+        None => panic!(
+            "assertion `left == right` failed\ndiffering fields:{}",
+            Report(diffs)
+        ),
+    }
+}
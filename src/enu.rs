@@ -0,0 +1,154 @@
+use crate::TypedString;
+use crate::__tuplez::search::{Complete, Unused};
+
+/// An anonymous enum, which is either the variant named `Name` carrying a `T`,
+/// or one of the variants in `Other`.
+///
+/// You usually do not use it yourself, see [`enu_t!`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Choice<Name, T, Other> {
+    /// The enum is of the variant named `Name`.
+    Here(Name, T),
+    /// The enum is of one of the remaining variants.
+    There(Other),
+}
+
+/// The anonymous enum without any variant, which has no value.
+///
+/// You usually do not use it yourself, see [`enu_t!`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Never {}
+
+/// A trait that indicate that an anonymous enum contains a certain variant.
+///
+/// # Generic parameters
+///
+/// * `Name`: Typed variant name, see [`ident!`](crate::ident!).
+/// * `T`: The type of data carried by the variant.
+/// * `R`: Type used to indicate the position of the variant in the enum.
+///   Usually automatically inferred by Rust.
+///
+/// # Example
+///
+/// ```
+/// use structz::*;
+///
+/// fn describe<E, R>(e: &E) -> String
+/// where
+///     E: HasVariant<ident!(failed), String, R>,
+/// {
+///     match e.get_variant() {
+///         Some(err) => format!("failed: {err}"),
+///         None => "ok".to_string(),
+///     }
+/// }
+///
+/// type Outcome = enu_t! { ok: u32, failed: String };
+/// let outcome: Outcome = enu!(failed: "timeout".to_string());
+/// assert_eq!(describe(&outcome), "failed: timeout");
+/// ```
+pub trait HasVariant<Name, T, R>
+where
+    Name: TypedString,
+{
+    /// The type of enum consisting of the remaining variants.
+    type Rest;
+
+    /// Build the enum of the variant that carries `value`.
+    fn from_variant(value: T) -> Self;
+
+    /// Get the immutable reference to the data carried by the variant,
+    /// or `None` if the enum is of another variant.
+    fn get_variant(&self) -> Option<&T>;
+
+    /// Get the mutable reference to the data carried by the variant,
+    /// or `None` if the enum is of another variant.
+    fn get_variant_mut(&mut self) -> Option<&mut T>;
+
+    /// Consume the enum and take the data carried by the variant,
+    /// or the enum of the remaining variants if the enum is of another variant.
+    fn take_variant(self) -> Result<T, Self::Rest>;
+}
+
+impl<Name, T, Other> HasVariant<Name, T, Complete> for Choice<Name, T, Other>
+where
+    Name: TypedString + Default,
+{
+    type Rest = Other;
+
+    fn from_variant(value: T) -> Self {
+        Choice::Here(Name::default(), value)
+    }
+
+    fn get_variant(&self) -> Option<&T> {
+        match self {
+            Choice::Here(_, value) => Some(value),
+            Choice::There(_) => None,
+        }
+    }
+
+    fn get_variant_mut(&mut self) -> Option<&mut T> {
+        match self {
+            Choice::Here(_, value) => Some(value),
+            Choice::There(_) => None,
+        }
+    }
+
+    fn take_variant(self) -> Result<T, Self::Rest> {
+        match self {
+            Choice::Here(_, value) => Ok(value),
+            Choice::There(other) => Err(other),
+        }
+    }
+}
+
+impl<Name, T, R, FirstName, FirstT, Other> HasVariant<Name, T, Unused<R>>
+    for Choice<FirstName, FirstT, Other>
+where
+    Name: TypedString,
+    Other: HasVariant<Name, T, R>,
+{
+    type Rest = Choice<FirstName, FirstT, Other::Rest>;
+
+    fn from_variant(value: T) -> Self {
+        Choice::There(Other::from_variant(value))
+    }
+
+    fn get_variant(&self) -> Option<&T> {
+        match self {
+            Choice::Here(..) => None,
+            Choice::There(other) => other.get_variant(),
+        }
+    }
+
+    fn get_variant_mut(&mut self) -> Option<&mut T> {
+        match self {
+            Choice::Here(..) => None,
+            Choice::There(other) => other.get_variant_mut(),
+        }
+    }
+
+    fn take_variant(self) -> Result<T, Self::Rest> {
+        match self {
+            Choice::Here(name, value) => Err(Choice::Here(name, value)),
+            Choice::There(other) => other.take_variant().map_err(Choice::There),
+        }
+    }
+}
+
+/// Helper trait used for [`match_enu!`] macro, which checks that all variants are matched.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`match_enu!` does not cover all variants of the anonymous enum",
+    label = "some variants are not covered",
+    note = "add an arm for each remaining variant, or a `_` arm"
+)]
+pub trait __Exhausted {
+    fn __absurd(self) -> !;
+}
+
+impl __Exhausted for Never {
+    fn __absurd(self) -> ! {
+        match self {}
+    }
+}
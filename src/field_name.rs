@@ -0,0 +1,202 @@
+use crate::__stringz::{Character, Chunk, Tagged};
+use crate::__tuplez::{Tuple, Unit};
+
+/// The maximum length in bytes of a field name obtained in constant evaluation,
+/// longer names are truncated and end with `...`.
+const CAPACITY: usize = 256;
+
+/// The maximum length in bytes of a field name in compile errors, longer names are cut with `...`.
+const NAME_LIMIT: usize = 64;
+
+/// A buffer that collects the UTF-8 encoding of a string in constant evaluation, without allocating.
+#[doc(hidden)]
+#[derive(Clone, Copy)]
+pub struct __NameBuf {
+    bytes: [u8; CAPACITY],
+    len: usize,
+    truncated: bool,
+}
+
+impl __NameBuf {
+    /// The empty buffer.
+    pub const EMPTY: Self = __NameBuf {
+        bytes: [0; CAPACITY],
+        len: 0,
+        truncated: false,
+    };
+
+    /// Append a byte to the buffer.
+    ///
+    /// If the buffer is full, the string is cut at a character boundary to make room for `...`,
+    /// and the following bytes are dropped.
+    pub const fn push_byte(mut self, byte: u8) -> Self {
+        if self.truncated {
+            return self;
+        }
+        if self.len == CAPACITY {
+            let mut len = CAPACITY - 3;
+            while len > 0 && self.bytes[len] & 0xC0 == 0x80 {
+                len -= 1;
+            }
+            self.bytes[len] = b'.';
+            self.bytes[len + 1] = b'.';
+            self.bytes[len + 2] = b'.';
+            self.len = len + 3;
+            self.truncated = true;
+            return self;
+        }
+        self.bytes[self.len] = byte;
+        self.len += 1;
+        self
+    }
+
+    /// Append the bytes of a string to the buffer.
+    pub const fn push_str(mut self, s: &str) -> Self {
+        let bytes = s.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            self = self.push_byte(bytes[i]);
+            i += 1;
+        }
+        self
+    }
+
+    /// Append the bytes of another buffer to the buffer.
+    const fn push_buf(mut self, other: &Self) -> Self {
+        let mut i = 0;
+        while i < other.len {
+            self = self.push_byte(other.bytes[i]);
+            i += 1;
+        }
+        self
+    }
+
+    /// Append the bytes of a string to the buffer, or `...` if there is not enough room left
+    /// for the string and another `...`.
+    pub const fn push_str_or_ellipsis(self, s: &str) -> Self {
+        if self.len + s.len() + 3 <= CAPACITY {
+            self.push_str(s)
+        } else if self.len + 3 <= CAPACITY {
+            self.push_str("...")
+        } else {
+            self
+        }
+    }
+
+    /// Append a field name to the buffer, cut with `...` if it is longer than 64 bytes.
+    pub const fn push_name(mut self, name: &str) -> Self {
+        let bytes = name.as_bytes();
+        if bytes.len() <= NAME_LIMIT {
+            return self.push_str(name);
+        }
+        let mut len = NAME_LIMIT - 3;
+        while len > 0 && bytes[len] & 0xC0 == 0x80 {
+            len -= 1;
+        }
+        let mut i = 0;
+        while i < len {
+            self = self.push_byte(bytes[i]);
+            i += 1;
+        }
+        self.push_str("...")
+    }
+
+    /// The collected string.
+    pub const fn as_str(&self) -> &str {
+        match core::str::from_utf8(self.bytes.split_at(self.len).0) {
+            Ok(s) => s,
+            Err(_) => panic!("field name is not valid UTF-8"),
+        }
+    }
+}
+
+/// Helper trait used for getting the original string of a field name type in constant evaluation,
+/// which unlike [`TypedString::value()`](crate::TypedString) does not need to allocate.
+#[doc(hidden)]
+pub trait __FieldName {
+    /// The UTF-8 encoding of the field name.
+    const __BUF: __NameBuf;
+
+    /// A `'static` reference to [`__BUF`](__FieldName::__BUF).
+    const __REF: &'static __NameBuf = &Self::__BUF;
+
+    /// The original string of the field name, truncated with `...` if it is longer than 256 bytes.
+    const __NAME: &'static str = Self::__REF.as_str();
+}
+
+impl __FieldName for Unit {
+    const __BUF: __NameBuf = __NameBuf::EMPTY;
+}
+
+impl<const C: char, Other> __FieldName for Tuple<Character<C>, Other>
+where
+    Other: __FieldName,
+{
+    const __BUF: __NameBuf = {
+        let mut utf8 = [0; 4];
+        let buf = __NameBuf::EMPTY.push_str(C.encode_utf8(&mut utf8));
+        buf.push_buf(Other::__REF)
+    };
+}
+
+impl<const BYTES: u128, const LEN: u8, Other> __FieldName for Tuple<Chunk<BYTES, LEN>, Other>
+where
+    Other: __FieldName,
+{
+    const __BUF: __NameBuf = {
+        let mut buf = __NameBuf::EMPTY;
+        let mut i = 0;
+        while i < LEN as u32 {
+            buf = buf.push_byte((BYTES >> (120 - 8 * i)) as u8);
+            i += 1;
+        }
+        buf.push_buf(Other::__REF)
+    };
+}
+
+impl<Tag, Field> __FieldName for Tagged<Tag, Field>
+where
+    Field: __FieldName,
+{
+    const __BUF: __NameBuf = Field::__BUF;
+}
+
+/// The edit distance between two field names, counting the transposition of two adjacent bytes
+/// as one edit, used to suggest the closest field for a misspelled one.
+pub const fn __edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() > CAPACITY || b.len() > CAPACITY {
+        return usize::MAX;
+    }
+    let mut prev2 = [0; CAPACITY + 1];
+    let mut prev = [0; CAPACITY + 1];
+    let mut curr = [0; CAPACITY + 1];
+    let mut j = 0;
+    while j <= b.len() {
+        prev[j] = j;
+        j += 1;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        curr[0] = i + 1;
+        let mut j = 0;
+        while j < b.len() {
+            let mut min = prev[j] + (a[i] != b[j]) as usize;
+            if prev[j + 1] + 1 < min {
+                min = prev[j + 1] + 1;
+            }
+            if curr[j] + 1 < min {
+                min = curr[j] + 1;
+            }
+            if i > 0 && j > 0 && a[i] == b[j - 1] && a[i - 1] == b[j] && prev2[j - 1] + 1 < min {
+                min = prev2[j - 1] + 1;
+            }
+            curr[j + 1] = min;
+            j += 1;
+        }
+        prev2 = prev;
+        prev = curr;
+        i += 1;
+    }
+    prev[b.len()]
+}
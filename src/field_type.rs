@@ -0,0 +1,189 @@
+use crate::__stringz::StrEq;
+use crate::__tuplez::{Tuple, Unit};
+use crate::{__FieldName, __NameBuf, __edit_distance};
+
+/// Find the position of a field in an anonymous struct type by the typed field name alone,
+/// in constant evaluation.
+///
+/// Together with [`FieldAt`], the type of a field can be projected from a concrete anonymous struct type
+/// without the position parameter of [`HasField`](crate::HasField). Optional fields are found by their plain
+/// names. The [`field_t!`](crate::field_t!) macro is a shorthand for the projection.
+///
+/// Since stable Rust cannot use generic parameters in constant expressions, the struct type must be concrete.
+///
+/// ```
+/// use structz::*;
+///
+/// type Person = stru_t! { name: &'static str, age: u8 };
+///
+/// type Age = <Person as FieldAt<{ <Person as FieldIndex<ident!(age)>>::CHECKED }>>::Type;
+/// let age: Age = 26u8;
+///
+/// assert_eq!(<Person as FieldIndex<ident!(name)>>::INDEX, 1);
+/// assert_eq!(<Person as FieldIndex<ident!(email)>>::INDEX, usize::MAX);
+/// ```
+pub trait FieldIndex<Field: __FieldName> {
+    /// The position of the field, or `usize::MAX` if there is no such field.
+    const INDEX: usize;
+
+    /// The names of the fields separated by `, `.
+    #[doc(hidden)]
+    const __NAMES: __NameBuf;
+
+    /// The name of the field closest to `Field`, and the edit distance between them.
+    #[doc(hidden)]
+    const __CLOSEST: (&'static str, usize);
+
+    /// The message reported if there is no such field.
+    #[doc(hidden)]
+    const __MISSING: __NameBuf = {
+        let name = Field::__NAME;
+        let mut msg = __NameBuf::EMPTY
+            .push_str("anonymous struct has no field `")
+            .push_name(name)
+            .push_str("`; available fields: ")
+            .push_str_or_ellipsis(Self::__NAMES.as_str());
+        let (closest, distance) = Self::__CLOSEST;
+        let max_distance = if name.len() < 3 { 1 } else { name.len() / 3 };
+        if distance <= max_distance {
+            msg = msg
+                .push_str_or_ellipsis("; did you mean `")
+                .push_str_or_ellipsis(__NameBuf::EMPTY.push_name(closest).as_str())
+                .push_str_or_ellipsis("`?");
+        }
+        msg
+    };
+
+    /// The position of the field, fails the compilation with the available fields if there is no such field.
+    const CHECKED: usize = if Self::INDEX == usize::MAX {
+        let msg = Self::__MISSING;
+        panic!("{}", msg.as_str())
+    } else {
+        Self::INDEX
+    };
+}
+
+impl<Field: __FieldName> FieldIndex<Field> for Unit {
+    const INDEX: usize = usize::MAX;
+
+    const __NAMES: __NameBuf = __NameBuf::EMPTY;
+
+    const __CLOSEST: (&'static str, usize) = ("", usize::MAX);
+}
+
+impl<Name, T, Other, Field> FieldIndex<Field> for Tuple<(Name, T), Other>
+where
+    Name: StrEq<Field> + __FieldName,
+    Field: __FieldName,
+    Other: FieldIndex<Field>,
+{
+    const INDEX: usize = if <Name as StrEq<Field>>::EQ {
+        0
+    } else if Other::INDEX == usize::MAX {
+        usize::MAX
+    } else {
+        Other::INDEX + 1
+    };
+
+    const __NAMES: __NameBuf = {
+        let name = __NameBuf::EMPTY.push_name(Name::__NAME);
+        let names = __NameBuf::EMPTY.push_str_or_ellipsis(name.as_str());
+        if Other::__NAMES.as_str().is_empty() {
+            names
+        } else {
+            names
+                .push_str_or_ellipsis(", ")
+                .push_str_or_ellipsis(Other::__NAMES.as_str())
+        }
+    };
+
+    const __CLOSEST: (&'static str, usize) = {
+        let name = Name::__NAME;
+        let distance = __edit_distance(name, Field::__NAME);
+        if distance <= Other::__CLOSEST.1 {
+            (name, distance)
+        } else {
+            Other::__CLOSEST
+        }
+    };
+}
+
+/// Get the type of data carried by the field at a position of an anonymous struct type,
+/// and the remaining fields without it.
+///
+/// The position of a field is usually found by [`FieldIndex`], and implemented for the first 64 positions.
+///
+/// ```
+/// use structz::*;
+///
+/// type Person = stru_t! { name: &'static str, age: u8 };
+///
+/// const AGE: usize = <Person as FieldIndex<ident!(age)>>::CHECKED;
+/// let _: <Person as FieldAt<AGE>>::Type = 26u8;
+/// let _: <Person as FieldAt<AGE>>::Rest = stru! { name: "John" };
+/// ```
+pub trait FieldAt<const N: usize> {
+    /// The typed name of the field, the typed names of optional fields are tagged as optional.
+    type Name;
+
+    /// The type of data carried by the field.
+    type Type;
+
+    /// The anonymous struct type consisting of the remaining fields.
+    type Rest;
+}
+
+impl<Name, T, Other> FieldAt<0> for Tuple<(Name, T), Other> {
+    type Name = Name;
+
+    type Type = T;
+
+    type Rest = Other;
+}
+
+macro_rules! impl_field_at {
+    ($($n:literal => $prev:literal),* $(,)?) => {$(
+        impl<Name, T, Other> FieldAt<$n> for Tuple<(Name, T), Other>
+        where
+            Other: FieldAt<$prev>,
+        {
+            type Name = Other::Name;
+
+            type Type = Other::Type;
+
+            type Rest = Tuple<(Name, T), Other::Rest>;
+        }
+    )*};
+}
+
+impl_field_at! {
+    1 => 0, 2 => 1, 3 => 2, 4 => 3, 5 => 4, 6 => 5, 7 => 6, 8 => 7,
+    9 => 8, 10 => 9, 11 => 10, 12 => 11, 13 => 12, 14 => 13, 15 => 14, 16 => 15,
+    17 => 16, 18 => 17, 19 => 18, 20 => 19, 21 => 20, 22 => 21, 23 => 22, 24 => 23,
+    25 => 24, 26 => 25, 27 => 26, 28 => 27, 29 => 28, 30 => 29, 31 => 30, 32 => 31,
+    33 => 32, 34 => 33, 35 => 34, 36 => 35, 37 => 36, 38 => 37, 39 => 38, 40 => 39,
+    41 => 40, 42 => 41, 43 => 42, 44 => 43, 45 => 44, 46 => 45, 47 => 46, 48 => 47,
+    49 => 48, 50 => 49, 51 => 50, 52 => 51, 53 => 52, 54 => 53, 55 => 54, 56 => 55,
+    57 => 56, 58 => 57, 59 => 58, 60 => 59, 61 => 60, 62 => 61, 63 => 62,
+}
+
+/// Helper function used for [`pick_t!`](crate::pick_t!) macro, which sorts the positions of the picked fields,
+/// so that the picked fields keep the order of the original anonymous struct type.
+#[doc(hidden)]
+pub const fn __sorted_positions<const N: usize>(mut positions: [usize; N]) -> [usize; N] {
+    let mut i = 1;
+    while i < N {
+        let mut j = i;
+        while j > 0 && positions[j - 1] > positions[j] {
+            let tmp = positions[j];
+            positions[j] = positions[j - 1];
+            positions[j - 1] = tmp;
+            j -= 1;
+        }
+        if j > 0 && positions[j - 1] == positions[j] {
+            panic!("field picked more than once");
+        }
+        i += 1;
+    }
+    positions
+}
@@ -5,7 +5,7 @@ use crate::__tuplez::{search::Search, Tuple};
 ///
 /// # Generic parameters
 ///
-/// * `Field`: Typed field name, see [`stringz::ident!`](https://docs.rs/stringz/0.1.2/stringz/macro.ident.html).
+/// * `Field`: Typed field name, see [`ident!`](crate::ident!).
 /// * `T`: The type of data carried by the field.
 /// * `R`: Type used to indicate the position of the field in the struct.
 ///   Usually automatically inferred by Rust.
@@ -17,6 +17,12 @@ pub trait HasField<Field, T, R>
 where
     Field: TypedString,
 {
+    /// The type of struct consisting of the remaining fields after taking the field out.
+    type Rest;
+
+    /// The type of struct that the data carried by the field is mapped to a value of type `U`.
+    type MapFieldOutput<U>;
+
     /// Get the immutable reference to the data carried by the field.
     fn get_field<'a>(&'a self) -> &'a T
     where
@@ -29,6 +35,17 @@ where
 
     /// Consume the struct and take the data carried by the field.
     fn take_field(self) -> T;
+
+    /// Consume the struct and take the data carried by the field, together with the remaining fields.
+    fn take_field_rest(self) -> (T, Self::Rest);
+
+    /// Replace the data carried by the field with `value`, returning the old data.
+    fn replace_field(&mut self, value: T) -> T;
+
+    /// Consume the struct and map the data carried by the field to a value that may be of a different type.
+    fn map_field<U, F>(self, f: F) -> Self::MapFieldOutput<U>
+    where
+        F: FnOnce(T) -> U;
 }
 
 impl<Field, T, R, First, Other> HasField<Field, T, R> for Tuple<First, Other>
@@ -36,6 +53,10 @@ where
     Field: TypedString,
     Self: Search<(Field, T), R>,
 {
+    type Rest = <Self as Search<(Field, T), R>>::TakeRemainder;
+
+    type MapFieldOutput<U> = <Self as Search<(Field, T), R>>::MapReplaceOutput<(Field, U)>;
+
     fn get_field<'a>(&'a self) -> &'a T
     where
         Field: 'a,
@@ -53,28 +74,20 @@ where
     fn take_field(self) -> T {
         Search::take(self).0 .1
     }
-}
 
-/// Helper trait used for [`field!`] macro.
-#[doc(hidden)]
-pub trait __GetFieldHelper {
-    #[doc(hidden)]
-    fn __get_field_helper<'a, Field, T, R>(&'a self) -> &'a T
-    where
-        Field: TypedString + 'a,
-        Self: HasField<Field, T, R>,
-    {
-        self.get_field()
+    fn take_field_rest(self) -> (T, Self::Rest) {
+        let ((_, value), rest) = Search::take(self);
+        (value, rest)
     }
 
-    #[doc(hidden)]
-    fn __get_field_mut_helper<'a, Field, T, R>(&'a mut self) -> &'a mut T
+    fn replace_field(&mut self, value: T) -> T {
+        core::mem::replace(&mut Search::get_mut(self).1, value)
+    }
+
+    fn map_field<U, F>(self, f: F) -> Self::MapFieldOutput<U>
     where
-        Field: TypedString + 'a,
-        Self: HasField<Field, T, R>,
+        F: FnOnce(T) -> U,
     {
-        self.get_field_mut()
+        Search::map_replace(self, |(field, value)| (field, f(value)))
     }
 }
-
-impl<T> __GetFieldHelper for T {}
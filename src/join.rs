@@ -0,0 +1,365 @@
+use crate::{TypedString, __FieldName};
+use crate::__tuplez::{Tuple, Unit};
+use core::{
+    fmt,
+    future::{Future, IntoFuture},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// The state of a future that is being joined, see [`Join`].
+#[doc(hidden)]
+pub enum MaybeDone<F: Future> {
+    Future(F),
+    Done(F::Output),
+    Gone,
+}
+
+impl<F: Future> MaybeDone<F> {
+    /// Poll the future if it has not completed yet, returns whether the output is ready.
+    fn poll_done(self: Pin<&mut Self>, cx: &mut Context<'_>) -> bool {
+        // SAFETY: The future is never moved out of the pinned state,
+        // it is dropped in place when replaced by its output.
+        unsafe {
+            let this = self.get_unchecked_mut();
+            match this {
+                MaybeDone::Future(f) => match Pin::new_unchecked(f).poll(cx) {
+                    Poll::Ready(output) => {
+                        *this = MaybeDone::Done(output);
+                        true
+                    }
+                    Poll::Pending => false,
+                },
+                MaybeDone::Done(_) => true,
+                MaybeDone::Gone => panic!("joined future polled after completion"),
+            }
+        }
+    }
+
+    /// Take the output out of the state, leaving it gone.
+    fn take_output(self: Pin<&mut Self>) -> F::Output {
+        // SAFETY: The state is only replaced when it holds the output, which is not pinned,
+        // so a pinned future is never moved.
+        let this = unsafe { self.get_unchecked_mut() };
+        match &mut *this {
+            MaybeDone::Done(_) => match core::mem::replace(this, MaybeDone::Gone) {
+                MaybeDone::Done(output) => output,
+                _ => unreachable!(),
+            },
+            _ => panic!("joined future polled after completion"),
+        }
+    }
+}
+
+/// A trait for anonymous structs whose fields are all futures, or can be converted into futures.
+///
+/// See [`join_fields!`].
+pub trait IntoJoinFields {
+    /// The anonymous struct that carries the states of the futures.
+    type States: JoinFields;
+
+    /// Start joining the futures.
+    fn into_join_states(self) -> Self::States;
+}
+
+impl IntoJoinFields for Unit {
+    type States = Unit;
+
+    fn into_join_states(self) -> Self::States {
+        Unit
+    }
+}
+
+impl<Field, F, Other> IntoJoinFields for Tuple<(Field, F), Other>
+where
+    Field: TypedString + Copy,
+    F: IntoFuture,
+    Other: IntoJoinFields,
+{
+    type States = Tuple<(Field, MaybeDone<F::IntoFuture>), Other::States>;
+
+    fn into_join_states(self) -> Self::States {
+        let Tuple((field, value), other) = self;
+        Tuple(
+            (field, MaybeDone::Future(value.into_future())),
+            other.into_join_states(),
+        )
+    }
+}
+
+/// A trait for anonymous structs that carry the states of the futures being joined.
+///
+/// You usually do not use it yourself, see [`join_fields!`].
+pub trait JoinFields {
+    /// The anonymous struct that carries the outputs of the futures.
+    type Output;
+
+    /// Poll all futures that have not completed yet, returns whether all of them have completed.
+    fn poll_join_fields(self: Pin<&mut Self>, cx: &mut Context<'_>) -> bool;
+
+    /// Take the outputs of the completed futures.
+    fn take_join_outputs(self: Pin<&mut Self>) -> Self::Output;
+}
+
+impl JoinFields for Unit {
+    type Output = Unit;
+
+    fn poll_join_fields(self: Pin<&mut Self>, _: &mut Context<'_>) -> bool {
+        true
+    }
+
+    fn take_join_outputs(self: Pin<&mut Self>) -> Self::Output {
+        Unit
+    }
+}
+
+impl<Field, F, Other> JoinFields for Tuple<(Field, MaybeDone<F>), Other>
+where
+    Field: TypedString + Copy,
+    F: Future,
+    Other: JoinFields,
+{
+    type Output = Tuple<(Field, F::Output), Other::Output>;
+
+    fn poll_join_fields(self: Pin<&mut Self>, cx: &mut Context<'_>) -> bool {
+        // SAFETY: Pinning is structural for every field of anonymous structs.
+        let (state, other) = unsafe {
+            let this = self.get_unchecked_mut();
+            (
+                Pin::new_unchecked(&mut this.0 .1),
+                Pin::new_unchecked(&mut this.1),
+            )
+        };
+        let done = state.poll_done(cx);
+        other.poll_join_fields(cx) && done
+    }
+
+    fn take_join_outputs(self: Pin<&mut Self>) -> Self::Output {
+        // SAFETY: Pinning is structural for every field of anonymous structs.
+        let (field, state, other) = unsafe {
+            let this = self.get_unchecked_mut();
+            (
+                this.0 .0,
+                Pin::new_unchecked(&mut this.0 .1),
+                Pin::new_unchecked(&mut this.1),
+            )
+        };
+        Tuple((field, state.take_output()), other.take_join_outputs())
+    }
+}
+
+/// A trait for anonymous structs that carry the states of the fallible futures being joined.
+///
+/// You usually do not use it yourself, see [`try_join_fields!`].
+pub trait TryJoinFields {
+    /// The anonymous struct that carries the `Ok` outputs of the futures.
+    type Output;
+
+    /// The error type shared by all futures.
+    type Error;
+
+    /// Poll all futures that have not completed yet, returns whether all of them have completed,
+    /// or the name and the error of the first future that failed.
+    fn try_poll_join_fields(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Result<bool, (&'static str, Self::Error)>;
+
+    /// Take the `Ok` outputs of the completed futures.
+    fn take_try_join_outputs(self: Pin<&mut Self>) -> Self::Output;
+}
+
+impl TryJoinFields for Unit {
+    type Output = Unit;
+
+    type Error = core::convert::Infallible;
+
+    fn try_poll_join_fields(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Result<bool, (&'static str, Self::Error)> {
+        Ok(true)
+    }
+
+    fn take_try_join_outputs(self: Pin<&mut Self>) -> Self::Output {
+        Unit
+    }
+}
+
+impl<Field, F, T, E> TryJoinFields for Tuple<(Field, MaybeDone<F>), Unit>
+where
+    Field: TypedString + __FieldName + Copy,
+    F: Future<Output = Result<T, E>>,
+{
+    type Output = Tuple<(Field, T), Unit>;
+
+    type Error = E;
+
+    fn try_poll_join_fields(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Result<bool, (&'static str, Self::Error)> {
+        // SAFETY: Pinning is structural for every field of anonymous structs.
+        let state = unsafe { self.map_unchecked_mut(|this| &mut this.0 .1) };
+        try_poll_done::<Field, F, T, E>(state, cx)
+    }
+
+    fn take_try_join_outputs(self: Pin<&mut Self>) -> Self::Output {
+        let Tuple((field, output), _) = self.take_join_outputs();
+        match output {
+            Ok(value) => Tuple((field, value), Unit),
+            Err(_) => unreachable!(),
+        }
+    }
+}
+
+impl<Field, F, T, E, Other, OtherFirst> TryJoinFields
+    for Tuple<(Field, MaybeDone<F>), Tuple<OtherFirst, Other>>
+where
+    Field: TypedString + __FieldName + Copy,
+    F: Future<Output = Result<T, E>>,
+    Tuple<OtherFirst, Other>: TryJoinFields<Error = E>,
+{
+    type Output = Tuple<(Field, T), <Tuple<OtherFirst, Other> as TryJoinFields>::Output>;
+
+    type Error = E;
+
+    fn try_poll_join_fields(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Result<bool, (&'static str, Self::Error)> {
+        // SAFETY: Pinning is structural for every field of anonymous structs.
+        let (state, other) = unsafe {
+            let this = self.get_unchecked_mut();
+            (
+                Pin::new_unchecked(&mut this.0 .1),
+                Pin::new_unchecked(&mut this.1),
+            )
+        };
+        let done = try_poll_done::<Field, F, T, E>(state, cx)?;
+        Ok(other.try_poll_join_fields(cx)? && done)
+    }
+
+    fn take_try_join_outputs(self: Pin<&mut Self>) -> Self::Output {
+        // SAFETY: Pinning is structural for every field of anonymous structs.
+        let (field, state, other) = unsafe {
+            let this = self.get_unchecked_mut();
+            (
+                this.0 .0,
+                Pin::new_unchecked(&mut this.0 .1),
+                Pin::new_unchecked(&mut this.1),
+            )
+        };
+        match state.take_output() {
+            Ok(value) => Tuple((field, value), other.take_try_join_outputs()),
+            Err(_) => unreachable!(),
+        }
+    }
+}
+
+/// Poll a fallible future, and take its error out if it has failed.
+fn try_poll_done<Field, F, T, E>(
+    mut state: Pin<&mut MaybeDone<F>>,
+    cx: &mut Context<'_>,
+) -> Result<bool, (&'static str, E)>
+where
+    Field: __FieldName,
+    F: Future<Output = Result<T, E>>,
+{
+    if !state.as_mut().poll_done(cx) {
+        return Ok(false);
+    }
+    if let MaybeDone::Done(Err(_)) = &*state {
+        if let Err(e) = state.take_output() {
+            return Err((Field::__NAME, e));
+        }
+    }
+    Ok(true)
+}
+
+/// A future that polls every future-valued field of an anonymous struct concurrently.
+///
+/// Created by [`join_fields!`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Join<S> {
+    states: S,
+}
+
+impl<S> Join<S> {
+    /// Start joining the futures of the fields of an anonymous struct.
+    pub fn new<Fields>(fields: Fields) -> Self
+    where
+        Fields: IntoJoinFields<States = S>,
+    {
+        Join {
+            states: fields.into_join_states(),
+        }
+    }
+}
+
+impl<S> Future for Join<S>
+where
+    S: JoinFields,
+{
+    type Output = S::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: The states are pinned structurally.
+        let mut states = unsafe { self.map_unchecked_mut(|this| &mut this.states) };
+        if states.as_mut().poll_join_fields(cx) {
+            Poll::Ready(states.take_join_outputs())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<S> fmt::Debug for Join<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Join")
+    }
+}
+
+/// A future that polls every fallible future-valued field of an anonymous struct concurrently,
+/// and short-circuits on the first error.
+///
+/// Created by [`try_join_fields!`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct TryJoin<S> {
+    states: S,
+}
+
+impl<S> TryJoin<S> {
+    /// Start joining the fallible futures of the fields of an anonymous struct.
+    pub fn new<Fields>(fields: Fields) -> Self
+    where
+        Fields: IntoJoinFields<States = S>,
+    {
+        TryJoin {
+            states: fields.into_join_states(),
+        }
+    }
+}
+
+impl<S> Future for TryJoin<S>
+where
+    S: TryJoinFields,
+{
+    type Output = Result<S::Output, (&'static str, S::Error)>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: The states are pinned structurally.
+        let mut states = unsafe { self.map_unchecked_mut(|this| &mut this.states) };
+        match states.as_mut().try_poll_join_fields(cx) {
+            Ok(true) => Poll::Ready(Ok(states.take_try_join_outputs())),
+            Ok(false) => Poll::Pending,
+            Err(e) => Poll::Ready(Err(e)),
+        }
+    }
+}
+
+impl<S> fmt::Debug for TryJoin<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TryJoin")
+    }
+}
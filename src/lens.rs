@@ -0,0 +1,214 @@
+use crate::TypedString;
+use crate::__tuplez::{search::Complete, Tuple, TupleLike, Unit};
+use crate::{HasField, Present, __Optional};
+use core::{fmt, marker::PhantomData};
+
+/// A trait for paths of typed field names that lead to a (possibly nested) field of an anonymous struct.
+///
+/// # Generic parameters
+///
+/// * `S`: The anonymous struct to look into.
+/// * `I`: Type used to indicate the positions of the fields along the path.
+///   Usually automatically inferred by Rust.
+pub trait Focus<S, I> {
+    /// The type of data carried by the field that the path leads to.
+    type Target;
+
+    /// Get the immutable reference to the data carried by the field that the path leads to.
+    fn focus<'a>(s: &'a S) -> &'a Self::Target
+    where
+        Self: 'a,
+        I: 'a;
+
+    /// Get the mutable reference to the data carried by the field that the path leads to.
+    fn focus_mut<'a>(s: &'a mut S) -> &'a mut Self::Target
+    where
+        Self: 'a,
+        I: 'a;
+}
+
+impl<S> Focus<S, Complete> for Unit {
+    type Target = S;
+
+    fn focus<'a>(s: &'a S) -> &'a Self::Target
+    where
+        Self: 'a,
+    {
+        s
+    }
+
+    fn focus_mut<'a>(s: &'a mut S) -> &'a mut Self::Target
+    where
+        Self: 'a,
+    {
+        s
+    }
+}
+
+impl<Field, Other, S, T, R, I> Focus<S, (T, R, I)> for Tuple<Field, Other>
+where
+    Field: TypedString,
+    S: HasField<Field, T, R>,
+    Other: Focus<T, I>,
+{
+    type Target = Other::Target;
+
+    fn focus<'a>(s: &'a S) -> &'a Self::Target
+    where
+        Self: 'a,
+        (T, R, I): 'a,
+    {
+        Other::focus(s.get_field())
+    }
+
+    fn focus_mut<'a>(s: &'a mut S) -> &'a mut Self::Target
+    where
+        Self: 'a,
+        (T, R, I): 'a,
+    {
+        Other::focus_mut(s.get_field_mut())
+    }
+}
+
+impl<Field, Other, S, T, R, I> Focus<S, Present<(T, R), I>> for Tuple<Field, Other>
+where
+    Field: TypedString,
+    S: HasField<__Optional<Field>, Option<T>, R>,
+    Other: Focus<Option<T>, I>,
+{
+    type Target = Other::Target;
+
+    fn focus<'a>(s: &'a S) -> &'a Self::Target
+    where
+        Self: 'a,
+        Present<(T, R), I>: 'a,
+    {
+        Other::focus(s.get_field())
+    }
+
+    fn focus_mut<'a>(s: &'a mut S) -> &'a mut Self::Target
+    where
+        Self: 'a,
+        Present<(T, R), I>: 'a,
+    {
+        Other::focus_mut(s.get_field_mut())
+    }
+}
+
+/// A first-class accessor of a (possibly nested) field of anonymous structs.
+///
+/// Unlike [`field!`], a lens is a value, which can be stored, passed around and composed.
+/// The lens itself is a zero-sized type, the generic parameter `Path` is a [`Tuple`] of typed field names.
+///
+/// Use [`lens!`] to create a lens.
+///
+/// ```
+/// use structz::*;
+///
+/// let mut config = stru! {
+///     db: stru! {
+///         pool: stru! { size: 8, timeout: 30 },
+///         url: "postgres://localhost",
+///     },
+///     name: "app",
+/// };
+///
+/// let pool_size = lens!(db).then(lens!(pool)).then(lens!(size));
+/// assert_eq!(pool_size.get(&config), &8);
+///
+/// pool_size.set(&mut config, 16);
+/// pool_size.modify(&mut config, |size| *size *= 2);
+/// assert_eq!(field!(&config.db), &stru! {
+///     pool: stru! { size: 32, timeout: 30 },
+///     url: "postgres://localhost",
+/// });
+///
+/// // A lens works for every struct that has the fields along the path
+/// let name = lens!(name);
+/// let person = stru! { name: "John", age: 26 };
+/// assert_eq!(name.get(&config), &"app");
+/// assert_eq!(name.get(&person), &"John");
+/// ```
+///
+/// A lens focuses on an optional field by its plain name, and the field carries an `Option`:
+///
+/// ```
+/// use structz::*;
+///
+/// let mut user: stru_t! { name: &str, nickname?: &str } = stru! { name: "John", .. };
+/// lens!(nickname).set(&mut user, Some("Johnny"));
+/// assert_eq!(field!(&user.nickname), Some(&"Johnny"));
+/// ```
+pub struct Lens<Path>(PhantomData<Path>);
+
+impl<Path> Lens<Path> {
+    /// Create a lens.
+    pub const fn new() -> Self {
+        Lens(PhantomData)
+    }
+
+    /// Compose two lenses, the new lens focuses on the field that `other` leads to
+    /// inside the field that `self` leads to.
+    pub fn then<Other>(self, _: Lens<Other>) -> Lens<Path::JoinOutput<Other>>
+    where
+        Path: TupleLike,
+        Other: TupleLike,
+    {
+        Lens::new()
+    }
+
+    /// Get the immutable reference to the data carried by the field that the lens focuses on.
+    pub fn get<'a, S, I>(&self, s: &'a S) -> &'a Path::Target
+    where
+        Path: Focus<S, I> + 'a,
+        I: 'a,
+    {
+        Path::focus(s)
+    }
+
+    /// Get the mutable reference to the data carried by the field that the lens focuses on.
+    pub fn get_mut<'a, S, I>(&self, s: &'a mut S) -> &'a mut Path::Target
+    where
+        Path: Focus<S, I> + 'a,
+        I: 'a,
+    {
+        Path::focus_mut(s)
+    }
+
+    /// Replace the data carried by the field that the lens focuses on, returning the old data.
+    pub fn set<S, I>(&self, s: &mut S, value: Path::Target) -> Path::Target
+    where
+        Path: Focus<S, I>,
+    {
+        core::mem::replace(Path::focus_mut(s), value)
+    }
+
+    /// Modify the data carried by the field that the lens focuses on in place.
+    pub fn modify<S, I, F>(&self, s: &mut S, f: F)
+    where
+        Path: Focus<S, I>,
+        F: FnOnce(&mut Path::Target),
+    {
+        f(Path::focus_mut(s))
+    }
+}
+
+impl<Path> Clone for Lens<Path> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Path> Copy for Lens<Path> {}
+
+impl<Path> Default for Lens<Path> {
+    fn default() -> Self {
+        Lens::new()
+    }
+}
+
+impl<Path> fmt::Debug for Lens<Path> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Lens")
+    }
+}
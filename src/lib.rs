@@ -157,6 +157,10 @@
 //! print_name_id(&earth);
 //! ```
 //!
+//! # Optional features
+//!
+//! * `alloc`: Enable APIs that need to allocate, such as `diff()` and `assert_stru_eq!`.
+//!
 //! # Details
 //!
 //! The implementation of structz is based on [stringz](https://docs.rs/stringz) and [tuplez](https://docs.rs/tuplez).
@@ -165,7 +169,14 @@
 //! with the same fields but different field orders are of the same type.
 //!
 //! Second, convert the field names into a specialized type consisting of a sequence of zero-sized types via
-//! [stringz](https://docs.rs/stringz). Let's call them "field name types".
+//! [stringz](https://docs.rs/stringz). Let's call them "field name types". The compact encoding of stringz is used,
+//! where each zero-sized type carries 16 bytes of the name, so long field names stay short types.
+//!
+//! **NOTE**: since structz 0.7, field name types are made by [`ident!`], i.e. `stringz::compact_ident!`,
+//! rather than `stringz::ident!`. Bounds written with `stringz::ident!` or `stringz::string!`,
+//! such as `HasField<stringz::ident!(name), T, R>`, no longer match the fields. The errors reported
+//! by structz name the fields as they are written, but types spelled out in other compiler errors
+//! show field names as `Chunk<..>`s of bytes instead of characters.
 //!
 //! Finally, pack the field name type and the data type of each field, combine them into
 //! [tuplez](https://docs.rs/tuplez)'s [`Tuple`](https://docs.rs/tuplez/latest/tuplez/struct.Tuple.html).
@@ -189,22 +200,61 @@
 
 #[macro_use]
 mod macros;
+mod anonymous;
+mod canonicalize;
+mod cfg;
+mod defaults;
+#[cfg(feature = "alloc")]
+mod diff;
+mod enu;
+mod field_name;
+mod field_type;
 mod has_field;
+mod join;
+mod lens;
+mod map_type;
+mod merge;
+mod optional;
+mod partial;
+mod transpose;
 
+pub use anonymous::*;
+pub use canonicalize::*;
+pub use cfg::*;
+pub use defaults::*;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use diff::*;
+pub use enu::*;
+pub use field_name::*;
+pub use field_type::*;
 pub use has_field::*;
+pub use join::*;
+pub use lens::*;
+pub use map_type::*;
+pub use merge::*;
+pub use optional::*;
+pub use partial::*;
+pub use transpose::*;
 
 extern crate self as structz;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 #[doc(hidden)]
 pub use stringz as __stringz;
 #[doc(hidden)]
 pub use stringz::__tuplez;
 
 #[doc(no_inline)]
-pub use stringz::{ident, TypedString};
+pub use stringz::{compact_ident as ident, TypedString};
 
 #[doc(hidden)]
-pub use structz_macros::{stru as stru_inner, stru_t as stru_t_inner};
+pub use structz_macros::{
+    enu_t as enu_t_inner, field_access as field_access_inner, stru as stru_inner,
+    stru_t as stru_t_inner,
+};
 
 /// Change the function's arguments to an anonymous struct object and unpack it.
 ///
@@ -241,4 +291,20 @@ pub use structz_macros::{stru as stru_inner, stru_t as stru_t_inner};
 /// num.add(stru! { x: 2, y: 3 });
 /// assert_eq!(num.0, 6);
 /// ```
+///
+/// Arguments can be compiled in or out by `#[cfg]` attributes:
+///
+/// ```
+/// use structz::*;
+///
+/// #[named_args]
+/// fn connect(host: &str, #[cfg(feature = "tls")] cert: &str) -> String {
+///     #[cfg(feature = "tls")]
+///     return format!("{host} with {cert}");
+///     #[cfg(not(feature = "tls"))]
+///     return host.to_string();
+/// }
+///
+/// assert_eq!(connect(stru! { host: "localhost" }), "localhost");
+/// ```
 pub use structz_macros::named_args;
@@ -43,21 +43,224 @@
 /// assert_eq!(field!(pos.y), 480);
 /// assert_eq!(field!(pos.marker), "Block");
 /// ```
+///
+/// To move a value of certain field out while keeping other fields usable, use [`take!`](crate::take!).
+///
+/// Optional fields (see [`stru_t!`](crate::stru_t!#optional-fields)) are accessed by their plain names,
+/// and yield `Option`s:
+///
+/// ```
+/// use structz::*;
+///
+/// type User = stru_t! {
+///     name: &'static str,
+///     nickname?: String,
+/// };
+///
+/// let mut user: User = stru! { name: "John", .. };
+/// assert_eq!(field!(&user.nickname), None);
+///
+/// let mut user: User = stru! { name: "John", nickname: "Johnny".to_string(), .. };
+/// field!(&mut user.nickname).unwrap().push('!');
+/// assert_eq!(field!(&user.nickname), Some(&"Johnny!".to_string()));
+/// assert_eq!(field!(user.nickname), Some("Johnny!".to_string()));
+/// ```
+///
+/// Fields named by string literals (see [`stru!`](crate::stru!)) are accessed by indexing with the name:
+///
+/// ```
+/// use structz::*;
+///
+/// let mut headers = stru! {
+///     "content-type": "text/html",
+///     "x-retry": 0,
+/// };
+/// *field!(&mut headers["x-retry"]) += 1;
+/// assert_eq!(field!(&headers["content-type"]), &"text/html");
+/// assert_eq!(field!(headers["x-retry"]), 1);
+/// ```
+///
+/// Accessing a field that does not exist is reported as
+/// "anonymous struct has no field `nmae`", which also applies to [`take!`](crate::take!),
+/// [`replace_field!`](crate::replace_field!), [`set_field!`](crate::set_field!),
+/// [`update_field!`](crate::update_field!), [`fields!`](crate::fields!) and
+/// [`fields_mut!`](crate::fields_mut!):
+///
+/// ```compile_fail
+/// use structz::*;
+///
+/// let person = stru! { name: "John Doe", age: 26 };
+/// let name = field!(&person.nmae);
+/// ```
+///
+/// Fields named by string literals are reported by the literals, e.g. "anonymous struct has no
+/// field `content-typ`". Since the type of the struct may be generic, `field!` cannot list the
+/// available fields, use [`field_t!`](crate::field_t!) on a concrete type for that.
+///
+/// ```compile_fail
+/// use structz::*;
+///
+/// let headers = stru! { "content-type": "text/html" };
+/// let content_type = field!(&headers["content-typ"]);
+/// ```
 #[macro_export]
 macro_rules! field {
     ($s:ident . $f:ident) => {
-        $crate::HasField::<$crate::ident!($f), _, _>::take_field($s)
+        $crate::field_access_inner!($crate; take; $s; $f)
+    };
+    (& $s:ident . $f:ident) => {
+        $crate::field_access_inner!($crate; get; $s; $f)
+    };
+    (&mut $s:ident . $f:ident) => {
+        $crate::field_access_inner!($crate; get_mut; $s; $f)
+    };
+    ($s:ident [ $f:literal ]) => {
+        $crate::field_access_inner!($crate; take; $s; $f)
+    };
+    (& $s:ident [ $f:literal ]) => {
+        $crate::field_access_inner!($crate; get; $s; $f)
+    };
+    (&mut $s:ident [ $f:literal ]) => {
+        $crate::field_access_inner!($crate; get_mut; $s; $f)
+    };
+}
+
+/// Move the value of a field out of an anonymous struct object, and get the remaining fields
+/// as a new anonymous struct object.
+///
+/// ```
+/// use structz::*;
+///
+/// let person = stru! {
+///     name: "John Doe".to_string(),
+///     age: 26,
+///     tags: vec!["developer", "rustacean"],
+/// };
+///
+/// let (tags, person) = take!(person.tags);
+/// assert_eq!(tags, vec!["developer", "rustacean"]);
+/// assert_eq!(person, stru! { name: "John Doe".to_string(), age: 26 });
+///
+/// let (name, person) = take!(person.name);
+/// assert_eq!(name, "John Doe");
+/// assert_eq!(person, stru! { age: 26 });
+/// ```
+#[macro_export]
+macro_rules! take {
+    ($s:ident . $f:ident) => {
+        $crate::field_access_inner!($crate; take_rest; $s; $f)
     };
-    (& $s:ident . $f:ident) => {{
-        use $crate::__GetFieldHelper;
-        $s.__get_field_helper::<$crate::ident!($f), _, _>()
+}
+
+/// Replace the value of a field of an anonymous struct object, returning the old value.
+///
+/// ```
+/// use structz::*;
+///
+/// let mut person = stru! {
+///     name: "John Doe",
+///     age: 26,
+/// };
+/// let old = replace_field!(person.age, 27);
+/// assert_eq!(old, 26);
+/// assert_eq!(field!(&person.age), &27);
+/// ```
+#[macro_export]
+macro_rules! replace_field {
+    ($s:ident . $f:ident, $v:expr $(,)?) => {{
+        let value = $v;
+        $crate::field_access_inner!($crate; replace; $s; $f; value)
     }};
-    (&mut $s:ident . $f:ident) => {{
-        use $crate::__GetFieldHelper;
-        $s.__get_field_mut_helper::<$crate::ident!($f), _, _>()
+}
+
+/// Consume an anonymous struct object and get a new one with the value of a field replaced.
+///
+/// Unlike [`replace_field!`](crate::replace_field!), the new value may be of a different type, in which case
+/// the new struct is of a different type too.
+///
+/// ```
+/// use structz::*;
+///
+/// let person = stru! {
+///     name: "John Doe",
+///     age: "thirty",
+/// };
+/// let person = set_field!(person.age = 30);
+/// assert_eq!(person, stru! { name: "John Doe", age: 30 });
+/// ```
+///
+/// Optional fields stay optional, so their new values are `Option`s:
+///
+/// ```
+/// use structz::*;
+///
+/// let user: stru_t! { name: &str, age?: &str } = stru! { name: "John Doe", .. };
+/// let user = set_field!(user.age = Some(30));
+/// let _: stru_t! { name: &str, age?: i32 } = user;
+/// ```
+#[macro_export]
+macro_rules! set_field {
+    ($s:ident . $f:ident = $v:expr) => {{
+        let value = $v;
+        $crate::field_access_inner!($crate; map; $s; $f; |_| value)
     }};
 }
 
+/// Consume an anonymous struct object and get a new one with the value of a field mapped
+/// by a function.
+///
+/// The function may return a value of a different type, in which case
+/// the new struct is of a different type too.
+///
+/// ```
+/// use structz::*;
+///
+/// let person = stru! {
+///     name: "John Doe",
+///     age: "30",
+/// };
+/// let person = update_field!(person.age, |age| age.parse::<u8>().unwrap());
+/// let person = update_field!(person.age, |age| age + 1);
+/// assert_eq!(person, stru! { name: "John Doe", age: 31 });
+/// ```
+///
+/// The values of optional fields are `Option`s, which are mapped to `Option`s, so that
+/// the fields stay optional:
+///
+/// ```
+/// use structz::*;
+///
+/// let user: stru_t! { name: &str, age?: &str } = stru! { name: "John Doe", age: "30", .. };
+/// let user = update_field!(user.age, |age| age.map(|age| age.parse::<u8>().unwrap()));
+/// let _: stru_t! { name: &str, age?: u8 } = user;
+/// ```
+#[macro_export]
+macro_rules! update_field {
+    ($s:ident . $f:ident, $func:expr $(,)?) => {
+        $crate::field_access_inner!($crate; map; $s; $f; $func)
+    };
+}
+
+/// Create a [`Lens`](crate::Lens) that focuses on a (possibly nested) field of anonymous structs.
+///
+/// ```
+/// use structz::*;
+///
+/// let config = stru! {
+///     db: stru! {
+///         pool: stru! { size: 8 },
+///     },
+/// };
+/// assert_eq!(lens!(db.pool.size).get(&config), &8);
+/// assert_eq!(lens!(db.pool).then(lens!(size)).get(&config), &8);
+/// ```
+#[macro_export]
+macro_rules! lens {
+    ($($f:ident).+) => {
+        $crate::Lens::<$crate::__tuplez::tuple_t!($($crate::ident!($f)),+)>::new()
+    };
+}
+
 /// Obtain a new struct, each field is an immutable reference to the corresponding field of the input struct.
 ///
 /// ```
@@ -70,7 +273,13 @@ macro_rules! field {
 /// };
 /// let ref_person = as_ref!(person);
 /// assert_eq!(field!(ref_person.age), &30);
+///
+/// // also works through a reference
+/// let ref_person = as_ref!(&person);
+/// assert_eq!(field!(ref_person.name), &"John");
 /// ```
+///
+/// See also the method form [`AnonymousStruct::fields_ref()`](crate::AnonymousStruct::fields_ref()).
 #[macro_export]
 macro_rules! as_ref {
     ($e:expr) => {{
@@ -96,7 +305,15 @@ macro_rules! as_ref {
 /// let mut_person = as_mut!(person);
 /// field!(mut_person.tags).pop();
 /// assert_eq!(field!(&person.tags), &vec!["smart"]);
+///
+/// // also works through a mutable reference
+/// let person_ref = &mut person;
+/// let mut_person = as_mut!(person_ref);
+/// *field!(mut_person.age) += 1;
+/// assert_eq!(field!(&person.age), &31);
 /// ```
+///
+/// See also the method form [`AnonymousStruct::fields_mut()`](crate::AnonymousStruct::fields_mut()).
 #[macro_export]
 macro_rules! as_mut {
     ($e:expr) => {{
@@ -109,6 +326,295 @@ macro_rules! as_mut {
     }};
 }
 
+/// Borrow multiple fields of an anonymous struct object immutably at the same time.
+///
+/// Returns a tuple of immutable references to the fields, in the order they are listed.
+///
+/// ```
+/// use structz::*;
+///
+/// let person = stru! {
+///     name: "John Doe",
+///     age: 26,
+///     tags: vec!["developer", "rustacean"],
+/// };
+/// let (tags, name) = fields!(&person; tags, name);
+/// assert_eq!(tags, &vec!["developer", "rustacean"]);
+/// assert_eq!(name, &"John Doe");
+/// ```
+///
+/// Like [`field!`], optional fields are borrowed as `Option`s of references:
+///
+/// ```
+/// use structz::*;
+///
+/// let user: stru_t! { name: &str, nickname?: &str } = stru! { name: "John", .. };
+/// let (name, nickname) = fields!(&user; name, nickname);
+/// assert_eq!(name, &"John");
+/// assert_eq!(nickname, None);
+/// ```
+#[macro_export]
+macro_rules! fields {
+    (& $s:ident; $($f:ident),+ $(,)?) => {{
+        #[allow(dead_code)]
+        struct FieldsMustBeDistinct {
+            $($f: (),)+
+        }
+        let rest = $crate::as_ref!($s);
+        $(
+            let ($f, rest) = $crate::field_access_inner!($crate; take_rest; rest; $f);
+        )+
+        let _ = rest;
+        ($($f,)+)
+    }};
+}
+
+/// Borrow multiple fields of an anonymous struct object mutably at the same time.
+///
+/// Returns a tuple of mutable references to the fields, in the order they are listed.
+/// The listed fields must be distinct, which is checked at compile time.
+///
+/// ```
+/// use structz::*;
+///
+/// let mut state = stru! {
+///     count: 3,
+///     total: 10,
+///     history: vec![],
+/// };
+/// let (count, total, history) = fields_mut!(&mut state; count, total, history);
+/// *total += *count;
+/// *count = 0;
+/// history.push(*total);
+/// assert_eq!(state, stru! { count: 0, total: 13, history: vec![13] });
+/// ```
+///
+/// Like [`field!`], optional fields are borrowed as `Option`s of references:
+///
+/// ```
+/// use structz::*;
+///
+/// let mut user: stru_t! { name: String, nickname?: String } = stru! {
+///     name: "John".to_string(),
+///     nickname: "Johnny".to_string(),
+///     ..
+/// };
+/// let (name, nickname) = fields_mut!(&mut user; name, nickname);
+/// name.push_str(" Doe");
+/// nickname.unwrap().push('!');
+/// assert_eq!(field!(&user.name), "John Doe");
+/// assert_eq!(field!(user.nickname), Some("Johnny!".to_string()));
+/// ```
+///
+/// ```compile_fail
+/// use structz::*;
+///
+/// let mut state = stru! { count: 3, total: 10 };
+/// let (a, b) = fields_mut!(&mut state; count, count);
+/// ```
+#[macro_export]
+macro_rules! fields_mut {
+    (&mut $s:ident; $($f:ident),+ $(,)?) => {{
+        #[allow(dead_code)]
+        struct FieldsMustBeDistinct {
+            $($f: (),)+
+        }
+        let rest = $crate::as_mut!($s);
+        $(
+            let ($f, rest) = $crate::field_access_inner!($crate; take_rest; rest; $f);
+        )+
+        let _ = rest;
+        ($($f,)+)
+    }};
+}
+
+/// Project a pinned mutable reference of an anonymous struct object to a new struct,
+/// each field is a pinned mutable reference to the corresponding field of the input struct.
+///
+/// This makes it possible to store futures or streams in anonymous structs and poll them
+/// without unsafe code:
+///
+/// ```
+/// use std::future::Future;
+/// use std::pin::pin;
+/// use std::task::{Context, Poll, Waker};
+/// use structz::*;
+///
+/// let mut futures = pin!(stru! {
+///     a: async { 1 },
+///     b: async { 2 },
+/// });
+/// let mut cx = Context::from_waker(Waker::noop());
+///
+/// let projected = as_pin_mut!(futures.as_mut());
+/// let (a, projected) = take!(projected.a);
+/// let (b, _) = take!(projected.b);
+/// assert_eq!(a.poll(&mut cx), Poll::Ready(1));
+/// assert_eq!(b.poll(&mut cx), Poll::Ready(2));
+/// ```
+///
+/// Fields that implement `Unpin` can be listed after `unpin:` to be projected to plain mutable references:
+///
+/// ```
+/// use std::future::{ready, Future, Ready};
+/// use std::pin::{pin, Pin};
+/// use std::task::{Context, Poll, Waker};
+/// use structz::*;
+///
+/// let mut futures = pin!(stru! {
+///     a: async { 1 },
+///     b: ready(2),
+/// });
+/// let mut cx = Context::from_waker(Waker::noop());
+///
+/// let projected = as_pin_mut!(futures.as_mut(); unpin: b);
+/// let (a, projected) = take!(projected.a);
+/// let (b, _) = take!(projected.b);
+/// let _: Pin<&mut _> = a;
+/// let b: &mut Ready<i32> = b;
+/// assert_eq!(Pin::new(b).poll(&mut cx), Poll::Ready(2));
+/// ```
+#[macro_export]
+macro_rules! as_pin_mut {
+    ($e:expr) => {
+        $crate::AnonymousStruct::fields_pin_mut($e)
+    };
+    ($e:expr; unpin: $($f:ident),+ $(,)?) => {{
+        let projected = $crate::AnonymousStruct::fields_pin_mut($e);
+        $(
+            let projected = $crate::field_access_inner!(
+                $crate; map; projected; $f; ::core::pin::Pin::into_inner
+            );
+        )+
+        projected
+    }};
+}
+
+/// Poll every future-valued field of an anonymous struct concurrently, and obtain a struct
+/// of their outputs with the same field names.
+///
+/// The fields may be anything that implements [`IntoFuture`](core::future::IntoFuture).
+/// The returned [`Join`](crate::Join) future does not depend on any executor, and is available in `no_std` environments.
+///
+/// ```
+/// use std::future::Future;
+/// use std::pin::pin;
+/// use std::task::{Context, Poll, Waker};
+/// use structz::*;
+///
+/// fn block_on<F: Future>(f: F) -> F::Output {
+///     let mut f = pin!(f);
+///     let mut cx = Context::from_waker(Waker::noop());
+///     loop {
+///         if let Poll::Ready(output) = f.as_mut().poll(&mut cx) {
+///             return output;
+///         }
+///     }
+/// }
+///
+/// async fn fetch_user(id: u32) -> &'static str {
+///     if id == 1 { "John" } else { "Unknown" }
+/// }
+///
+/// async fn fetch_orders(user: &str) -> Vec<u32> {
+///     if user == "John" { vec![101, 102] } else { vec![] }
+/// }
+///
+/// let result = block_on(async {
+///     join_fields!(stru! {
+///         user: fetch_user(1),
+///         orders: fetch_orders("John"),
+///     })
+///     .await
+/// });
+/// assert_eq!(result, stru! { orders: vec![101, 102], user: "John" });
+/// ```
+#[macro_export]
+macro_rules! join_fields {
+    ($e:expr $(,)?) => {
+        $crate::Join::new($e)
+    };
+}
+
+/// Poll every fallible future-valued field of an anonymous struct concurrently.
+///
+/// The outputs of the futures must be `Result`s with the same error type.
+/// The returned [`TryJoin`](crate::TryJoin) future resolves to a struct of the `Ok` values if all futures succeed,
+/// otherwise it short-circuits on the first error, and resolves to the name of the failed field
+/// together with the error. Like [`join_fields!`](crate::join_fields!), it is available in `no_std`
+/// environments, since the field name is a `&'static str` obtained at compile time.
+///
+/// ```
+/// use std::future::Future;
+/// use std::pin::pin;
+/// use std::task::{Context, Poll, Waker};
+/// use structz::*;
+///
+/// fn block_on<F: Future>(f: F) -> F::Output {
+///     let mut f = pin!(f);
+///     let mut cx = Context::from_waker(Waker::noop());
+///     loop {
+///         if let Poll::Ready(output) = f.as_mut().poll(&mut cx) {
+///             return output;
+///         }
+///     }
+/// }
+///
+/// async fn parse(s: &str) -> Result<u8, std::num::ParseIntError> {
+///     s.parse()
+/// }
+///
+/// let result = block_on(try_join_fields!(stru! {
+///     age: parse("26"),
+///     grade: parse("3"),
+/// }));
+/// assert_eq!(result, Ok(stru! { age: 26, grade: 3 }));
+///
+/// let result = block_on(try_join_fields!(stru! {
+///     age: parse("twenty-six"),
+///     grade: parse("3"),
+/// }));
+/// let (field, _) = result.unwrap_err();
+/// assert_eq!(field, "age");
+/// ```
+///
+/// Field names of any length are supported, but names longer than 256 bytes are reported
+/// truncated, ending with `...`:
+///
+/// ```
+/// # use std::future::Future;
+/// # use std::pin::pin;
+/// # use std::task::{Context, Poll, Waker};
+/// use structz::*;
+///
+/// # fn block_on<F: Future>(f: F) -> F::Output {
+/// #     let mut f = pin!(f);
+/// #     let mut cx = Context::from_waker(Waker::noop());
+/// #     loop {
+/// #         if let Poll::Ready(output) = f.as_mut().poll(&mut cx) {
+/// #             return output;
+/// #         }
+/// #     }
+/// # }
+/// async fn parse(s: &str) -> Result<u8, std::num::ParseIntError> {
+///     s.parse()
+/// }
+///
+/// let result = block_on(try_join_fields!(stru! {
+///     aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa: parse("x"),
+///     b: parse("3"),
+/// }));
+/// let (field, _) = result.unwrap_err();
+/// assert_eq!(field.len(), 256);
+/// assert!(field.starts_with("aaa") && field.ends_with("a..."));
+/// ```
+#[macro_export]
+macro_rules! try_join_fields {
+    ($e:expr $(,)?) => {
+        $crate::TryJoin::new($e)
+    };
+}
+
 /// Create anonymous struct object.
 ///
 /// Just like how an object of named struct is created, you declare a field name,
@@ -138,39 +644,925 @@ macro_rules! as_mut {
 /// };
 /// assert_eq!(field!(person.name), "Smith");
 /// ```
-#[macro_export]
-macro_rules! stru {
-    ($($t:tt)*) => {
-        $crate::stru_inner!($crate; $($t)*)
-    };
-}
-
-/// Generate anonymous struct type.
-///
-/// Sometimes you may need to know the exact type of an anonymous struct object.
 ///
-/// Just like how a named struct type is declared, you declare a field name,
-/// followed by a colon, and then its type. Finally, you separate each field with commas.
+/// The captured variable can be prefixed with `ref`, `ref mut` or `clone` to capture it
+/// by reference or by cloning it. A path of fields, like `person.age` or `self.id`,
+/// can also be captured, and its last segment is used as the field name:
 ///
 /// ```
 /// use structz::*;
 ///
-/// type Person = stru_t! {
-///     name: &'static str,
+/// struct Order {
+///     id: u32,
+///     items: Vec<&'static str>,
+/// }
+///
+/// struct Person {
 ///     age: u8,
+///     email: String,
+/// }
+///
+/// impl Order {
+///     fn summary(&self) -> stru_t! { id: u32, count: usize, items: Vec<&'static str> } {
+///         let count = self.items.len();
+///         stru! { self.id, count, clone self.items }
+///     }
+/// }
+///
+/// let order = Order { id: 1, items: vec!["book", "pen"] };
+/// assert_eq!(order.summary(), stru! { id: 1, count: 2, items: vec!["book", "pen"] });
+///
+/// let name = String::from("John");
+/// let mut tags = vec!["developer"];
+/// let person = Person { age: 26, email: String::from("john@example.com") };
+/// let info = stru! { ref name, ref mut tags, person.age, clone person.email };
+/// field!(info.tags).push("rustacean");
+/// assert_eq!(tags, vec!["developer", "rustacean"]);
+/// assert_eq!(person.email, "john@example.com");
+/// ```
+///
+/// Keywords can be used as field names by raw identifiers, the `r#` prefix is not part of the name:
+///
+/// ```
+/// use structz::*;
+///
+/// let token = stru! { r#type: "keyword", r#match: true };
+/// assert_eq!(field!(&token.r#type), &"keyword");
+/// assert_eq!(token, stru! { "type": "keyword", "match": true });
+///
+/// #[named_args]
+/// fn describe(r#type: &str, name: &str) -> String {
+///     format!("{name}: {type}", type = r#type)
+/// }
+/// assert_eq!(describe(stru! { r#type: "u8", name: "age" }), "age: u8");
+/// ```
+///
+/// Field names that are not Rust identifiers can be written as string literals of any non-empty string,
+/// and are sorted together with the other field names:
+///
+/// ```
+/// use structz::*;
+///
+/// type Event = stru_t! {
+///     "@timestamp": u64,
+///     "2xx": u32,
+///     message: &'static str,
 /// };
 ///
-/// let person: Person = stru! {
-///     age: 15,
-///     name: "Alice",
+/// let event: Event = stru! {
+///     message: "ok",
+///     "2xx": 200,
+///     "@timestamp": 1700000000,
 /// };
+/// assert_eq!(field!(&event["2xx"]), &200);
+///
+/// let query = stru! { "?page": 2, page: "index" };
+/// assert_eq!(field!(query["?page"]), 2);
+///
+/// // Identifiers and string literals with the same content name the same field
+/// assert_eq!(event, stru! { "message": "ok", "2xx": 200, "@timestamp": 1700000000 });
 /// ```
 ///
-/// For cases where the anonymous structs are used as function arguments, it is recommended
-/// that you use the [`macro@named_args`] instead.
-#[macro_export]
-macro_rules! stru_t {
-    ($($t:tt)*) => {
-        $crate::stru_t_inner!($crate; $($t)*)
+/// A brace-delimited value is a nested anonymous struct, so deeply nested structs need not
+/// repeat `stru!` (wrap a block expression in parentheses to use it as a value):
+///
+/// ```
+/// use structz::*;
+///
+/// let config = stru! {
+///     db: {
+///         host: "localhost",
+///         port: 5432,
+///     },
+///     log: { level: 3 },
+///     workers: ({ let cores = 4; cores * 2 }),
+/// };
+/// assert_eq!(config, stru! {
+///     db: stru! { host: "localhost", port: 5432 },
+///     log: stru! { level: 3 },
+///     workers: 8,
+/// });
+/// ```
+///
+/// Optional fields (see [`stru_t!`](crate::stru_t!#optional-fields)) are declared by `name?`,
+/// and take `Option`s. Ending the fields with `..` leaves the omitted optional fields absent,
+/// in which case the type of the anonymous struct must be known, and the given optional
+/// fields can also take plain values by their plain names:
+///
+/// ```
+/// use structz::*;
+///
+/// type User = stru_t! {
+///     name: &'static str,
+///     nickname?: &'static str,
+///     email?: &'static str,
+/// };
+///
+/// let user: User = stru! { name: "John", .. };
+/// let user: User = stru! { name: "John", nickname: "Johnny", .. };
+/// let user: User = stru! { name: "John", email?: None, nickname?: Some("Johnny") };
+///
+/// // Optional fields are reported by their plain names
+/// let missing = stru! { nickname?: None::<&str>, email?: Some("john@example.com") }
+///     .transpose_option_or_missing()
+///     .unwrap_err();
+/// assert!(missing.eq(["nickname"]));
+/// ```
+///
+/// Fields can be compiled in or out by `#[cfg]` attributes:
+///
+/// ```
+/// use structz::*;
+///
+/// let request = stru! {
+///     path: "/",
+///     #[cfg(feature = "metrics")]
+///     latency: std::time::Duration::from_millis(12),
+/// };
+/// assert_eq!(request, stru! { path: "/" });
+/// ```
+#[macro_export]
+macro_rules! stru {
+    ($($t:tt)*) => {
+        $crate::stru_inner!($crate; $($t)*)
+    };
+}
+
+/// Generate anonymous struct type.
+///
+/// Sometimes you may need to know the exact type of an anonymous struct object.
+///
+/// Just like how a named struct type is declared, you declare a field name,
+/// followed by a colon, and then its type. Finally, you separate each field with commas.
+///
+/// ```
+/// use structz::*;
+///
+/// type Person = stru_t! {
+///     name: &'static str,
+///     age: u8,
+/// };
+///
+/// let person: Person = stru! {
+///     age: 15,
+///     name: "Alice",
+/// };
+/// ```
+///
+/// A brace-delimited type is a nested anonymous struct type:
+///
+/// ```
+/// use structz::*;
+///
+/// type Config = stru_t! {
+///     db: { host: &'static str, port: u16 },
+///     log: { level: u8 },
+/// };
+///
+/// let config: Config = stru! {
+///     db: { host: "localhost", port: 5432 },
+///     log: { level: 3 },
+/// };
+/// let _: stru_t! { host: &'static str, port: u16 } = field!(config.db);
+/// ```
+///
+/// For cases where the anonymous structs are used as function arguments, it is recommended
+/// that you use the [`macro@named_args`] instead.
+///
+/// # Default values
+///
+/// If the types of all fields implement `Default`, the anonymous struct type implements
+/// `Default` too, and each field takes its default value:
+///
+/// ```
+/// use structz::*;
+///
+/// type Options = stru_t! {
+///     port: u16,
+///     host: String,
+/// };
+///
+/// let options = Options::default();
+/// assert_eq!(options, stru! { host: String::new(), port: 0 });
+/// ```
+///
+/// To declare other default values, follow the types of fields by `=` and the default values.
+/// Fields without declared default values take `Default::default()`. The anonymous struct type
+/// is still the same type, and implements [`DeclaredDefaults`](crate::DeclaredDefaults), which is used by
+/// [`stru_default!`](crate::stru_default!) to fill the remaining fields:
+///
+/// ```
+/// use structz::*;
+///
+/// /// Options of the server.
+/// pub type Options = stru_t! {
+///     port: u16 = 8080,
+///     host: String,
+///     workers: usize = 4,
+///     tls: { enabled: bool, port: u16 = 8443 },
+/// };
+///
+/// let options: Options = stru_default! {
+///     host: "localhost".to_string(),
+/// };
+/// assert_eq!(field!(&options.port), &8080);
+/// assert_eq!(field!(&options.workers), &4);
+/// assert_eq!(field!(options.tls), stru! { enabled: false, port: 8443 });
+/// ```
+///
+/// `Default::default()` ignores the declared default values. Since the default values are registered
+/// for the anonymous struct type, declare them once for the same fields, usually in a type alias,
+/// and the type cannot depend on generic parameters. Declaring different default values for the
+/// same fields fails the compilation of [`stru_default!`](crate::stru_default!) for them:
+///
+/// ```compile_fail
+/// use structz::*;
+///
+/// type Options = stru_t! {
+///     port: u16 = 8080,
+///     host: String,
+/// };
+///
+/// type TestOptions = stru_t! {
+///     port: u16 = 9090,
+///     host: String,
+/// };
+///
+/// let options: Options = stru_default! {
+///     host: "localhost".into(),
+/// };
+/// ```
+///
+/// # Optional fields
+///
+/// Follow the name of a field by `?` to make it optional, then it carries an `Option`
+/// of the declared type, and can be omitted when creating the anonymous struct by ending
+/// the fields with `..` in [`stru!`](crate::stru!). [`field!`](crate::field!) yields an `Option`
+/// for optional fields:
+///
+/// ```
+/// use structz::*;
+///
+/// type Payload = stru_t! {
+///     name: String,
+///     nickname?: String,
+/// };
+///
+/// fn greet(payload: Payload) -> String {
+///     match field!(&payload.nickname) {
+///         Some(nickname) => format!("Hi, {nickname}!"),
+///         None => format!("Hello, {}.", field!(&payload.name)),
+///     }
+/// }
+///
+/// assert_eq!(greet(stru! { name: "John".to_string(), .. }), "Hello, John.");
+/// assert_eq!(
+///     greet(stru! { name: "John".to_string(), nickname: "Johnny".to_string(), .. }),
+///     "Hi, Johnny!",
+/// );
+/// ```
+///
+/// The `..` is required. Without it, [`stru!`](crate::stru!) creates exactly the given fields,
+/// just like a named struct expression, so an anonymous struct that omits an optional field is
+/// of a different type. Filling in the absent fields needs the target type, which is only known
+/// at the `..` spelling:
+///
+/// ```compile_fail
+/// use structz::*;
+///
+/// type Payload = stru_t! {
+///     name: String,
+///     nickname?: String,
+/// };
+///
+/// let payload: Payload = stru! { name: "John".to_string() };
+/// ```
+///
+/// Optional fields are not visible to [`HasField`](crate::HasField) by their plain names,
+/// since their typed names are tagged as optional, but they are still reported by their plain names,
+/// e.g. by [`transpose_option_or_missing()`](crate::TransposeOption::transpose_option_or_missing).
+/// See also [`FillOptional`](crate::FillOptional).
+///
+/// # Conditional fields
+///
+/// Fields can be compiled in or out by `#[cfg]` attributes, each field on its own,
+/// and the anonymous struct type consists of the surviving fields:
+///
+/// ```
+/// use std::time::Duration;
+/// use structz::*;
+///
+/// stru_t! {
+///     pub type Request = {
+///         path: &'static str,
+///         #[cfg(feature = "metrics")]
+///         latency: Duration,
+///         #[cfg(unix)]
+///         socket: &'static str,
+///         #[cfg(not(unix))]
+///         socket: u16,
+///     };
+/// }
+///
+/// let request: Request = stru! {
+///     path: "/",
+///     #[cfg(feature = "metrics")]
+///     latency: Duration::from_millis(12),
+///     #[cfg(unix)]
+///     socket: "/tmp/app.sock",
+///     #[cfg(not(unix))]
+///     socket: 8080,
+/// };
+///
+/// fn handle(_: stru_t! { path: &'static str, #[cfg(feature = "metrics")] latency: Duration }) {}
+/// handle(stru! { path: "/" });
+/// ```
+///
+/// Fields with the same name must not be compiled in together. The lifetimes in the types of
+/// conditional fields must be named, e.g. `&'a str` instead of `&str`.
+#[macro_export]
+macro_rules! stru_t {
+    ($($t:tt)*) => {
+        $crate::stru_t_inner! { $crate; $($t)* }
+    };
+}
+
+/// Get the type of data carried by a (possibly nested) field of an anonymous struct type.
+///
+/// The struct type must be named by a path, usually a type alias declared with [`stru_t!`],
+/// so that signatures keep in sync with the alias:
+///
+/// ```
+/// use structz::*;
+///
+/// type Person = stru_t! {
+///     name: String,
+///     age: u8,
+///     address: stru_t! { city: String, zip: u32 },
+/// };
+///
+/// fn parse_age(x: &str) -> field_t!(Person.age) {
+///     x.parse().unwrap()
+/// }
+///
+/// let zip: field_t!(Person.address.zip) = 10001;
+/// let person: Person = stru! {
+///     name: "John Doe".to_string(),
+///     age: parse_age("26"),
+///     address: stru! { city: "New York".to_string(), zip },
+/// };
+/// assert_eq!(field!(person.age), 26u8);
+/// ```
+///
+/// Optional fields are named by their plain names, and carry an `Option`.
+/// Fields named by string literals are named by indexing with the name:
+///
+/// ```
+/// use structz::*;
+///
+/// type Headers = stru_t! {
+///     "content-type": &'static str,
+///     retries?: u8,
+/// };
+///
+/// let content_type: field_t!(Headers["content-type"]) = "text/html";
+/// let retries: field_t!(Headers.retries) = Some(3);
+/// ```
+///
+/// Field names of any length can be looked up, though names longer than 256 bytes are truncated
+/// with `...` in compile errors:
+///
+/// ```
+/// use structz::*;
+///
+/// type Big = stru_t! {
+///     aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa: u8,
+///     b: u16,
+/// };
+///
+/// let b: field_t!(Big.b) = 3;
+/// let a: field_t!(Big.aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa) = 1;
+/// ```
+///
+/// The field is looked up in constant evaluation by [`FieldIndex`](crate::FieldIndex) and [`FieldAt`](crate::FieldAt),
+/// so the struct type cannot depend on generic parameters,
+/// and it may have at most 64 fields. Naming a field that does not exist fails the compilation
+/// with the available fields and the closest one, e.g. "anonymous struct has no field `aeg`;
+/// available fields: age, name; did you mean `age`?":
+///
+/// ```compile_fail
+/// use structz::*;
+///
+/// type Person = stru_t! { name: String, age: u8 };
+/// let age: field_t!(Person.aeg) = 26;
+/// ```
+#[macro_export]
+macro_rules! field_t {
+    ($($s:ident)::+ $(. $f:ident)+) => {
+        $crate::field_t!(@ ($($s)::+) $(. $f)+)
+    };
+    ($($s:ident)::+ [ $f:literal ]) => {
+        $crate::field_t!(@ ($($s)::+) [$f])
+    };
+    (@ ($t:ty) . $f:ident $($rest:tt)*) => {
+        $crate::field_t!(@ ($crate::field_t!(@ $t, $crate::ident!($f))) $($rest)*)
+    };
+    (@ ($t:ty) [ $f:literal ]) => {
+        $crate::field_t!(@ $t, $crate::__stringz::compact_string!($f))
+    };
+    (@ ($t:ty)) => {
+        $t
+    };
+    (@ $t:ty, $f:ty) => {
+        <$t as $crate::FieldAt<{ <$t as $crate::FieldIndex<$f>>::CHECKED }>>::Type
+    };
+}
+
+/// Get an anonymous struct type consisting of some fields of another anonymous struct type.
+///
+/// Like [`field_t!`], the struct type must be named by a path, usually a type alias declared with [`stru_t!`]:
+///
+/// ```
+/// use structz::*;
+///
+/// type User = stru_t! {
+///     id: u64,
+///     name: String,
+///     password: String,
+///     "e-mail": String,
+/// };
+///
+/// type UserSummary = pick_t!(User; id, name, "e-mail");
+///
+/// let summary: UserSummary = stru! {
+///     id: 1,
+///     name: "John Doe".to_string(),
+///     "e-mail": "john@example.com".to_string(),
+/// };
+/// ```
+///
+/// Optional fields stay optional:
+///
+/// ```
+/// use structz::*;
+///
+/// type User = stru_t! {
+///     id: u64,
+///     name: String,
+///     nickname?: String,
+/// };
+///
+/// type Names = pick_t!(User; name, nickname);
+///
+/// let names: Names = stru! { name: "John Doe".to_string(), .. };
+/// assert_eq!(field!(names.nickname), None);
+/// ```
+#[macro_export]
+macro_rules! pick_t {
+    ($($s:ident)::+; $($f:tt),+ $(,)?) => {
+        $crate::pick_t!(@ ($($s)::+); [$($crate::pick_t!(@ $f)),+]; (0); $($f)+)
+    };
+    (@ $f:ident) => {
+        $crate::ident!($f)
+    };
+    (@ $f:literal) => {
+        $crate::__stringz::compact_string!($f)
+    };
+    (@ ($t:ty); [$($n:ty),+]; ($i:expr);) => {
+        $crate::__tuplez::Unit
+    };
+    (@ ($t:ty); [$($n:ty),+]; ($i:expr); $f:tt $($rest:tt)*) => {
+        $crate::__tuplez::Tuple<
+            (
+                <$t as $crate::FieldAt<{
+                    $crate::__sorted_positions([$(<$t as $crate::FieldIndex<$n>>::CHECKED),+])[$i]
+                }>>::Name,
+                <$t as $crate::FieldAt<{
+                    $crate::__sorted_positions([$(<$t as $crate::FieldIndex<$n>>::CHECKED),+])[$i]
+                }>>::Type,
+            ),
+            $crate::pick_t!(@ ($t); [$($n),+]; ($i + 1); $($rest)*),
+        >
+    };
+}
+
+/// Get an anonymous struct type consisting of the fields of another anonymous struct type
+/// except the listed ones.
+///
+/// Like [`field_t!`], the struct type must be named by a path, usually a type alias declared with [`stru_t!`]:
+///
+/// ```
+/// use structz::*;
+///
+/// type User = stru_t! {
+///     id: u64,
+///     name: String,
+///     password: String,
+///     nickname?: String,
+/// };
+///
+/// type NewUser = omit_t!(User; id);
+/// type PublicUser = omit_t!(User; password);
+///
+/// let new_user: NewUser = stru! {
+///     name: "John Doe".to_string(),
+///     password: "secret".to_string(),
+///     ..
+/// };
+/// let public_user: PublicUser = stru! { id: 1, name: "John Doe".to_string(), .. };
+/// ```
+#[macro_export]
+macro_rules! omit_t {
+    ($($s:ident)::+; $($f:tt),+ $(,)?) => {
+        $crate::omit_t!(@ ($($s)::+) $($f)+)
+    };
+    (@ ($t:ty)) => {
+        $t
+    };
+    (@ ($t:ty) $f:ident $($rest:tt)*) => {
+        $crate::omit_t!(@ ($crate::omit_t!(@ $t, $crate::ident!($f))) $($rest)*)
+    };
+    (@ ($t:ty) $f:literal $($rest:tt)*) => {
+        $crate::omit_t!(@ ($crate::omit_t!(@ $t, $crate::__stringz::compact_string!($f))) $($rest)*)
+    };
+    (@ $t:ty, $f:ty) => {
+        <$t as $crate::FieldAt<{ <$t as $crate::FieldIndex<$f>>::CHECKED }>>::Rest
+    };
+}
+
+/// Get an anonymous struct type consisting of the fields of two anonymous struct types.
+///
+/// This is not a `Merge<A, B>` type operator. The fields are matched by comparing their names in
+/// constant evaluation, which is only possible for concrete types, so `merge_t!` cannot be applied
+/// to generic parameters.
+///
+/// If both struct types have a field of the same name, the field of the second one is taken.
+/// Like [`field_t!`], the struct types must be named by paths, usually type aliases declared with [`stru_t!`],
+/// and each of them and the result can have at most 64 fields:
+///
+/// ```
+/// use structz::*;
+///
+/// type Record = stru_t! {
+///     id: u64,
+///     name: String,
+/// };
+///
+/// type Timestamps = stru_t! {
+///     created_at: u64,
+///     updated_at?: u64,
+/// };
+///
+/// type Response = merge_t!(Record, Timestamps);
+///
+/// let response: Response = stru! {
+///     id: 1,
+///     name: "John Doe".to_string(),
+///     created_at: 1700000000,
+///     ..
+/// };
+/// assert_eq!(field!(response.updated_at), None);
+/// ```
+///
+/// The result is the same type as [`stru_t!`] gives for the merged fields:
+///
+/// ```
+/// use structz::*;
+///
+/// type Base = stru_t! { id: u64, name: String, age: u8 };
+/// type Patch = stru_t! { age?: u8, email: String };
+///
+/// let merged: merge_t!(Base, Patch) = stru! {
+///     id: 1,
+///     name: "John Doe".to_string(),
+///     email: "john@example.com".to_string(),
+///     ..
+/// };
+/// let _: stru_t! { id: u64, name: String, age?: u8, email: String } = merged;
+/// ```
+#[macro_export]
+macro_rules! merge_t {
+    ($($a:ident)::+, $($b:ident)::+ $(,)?) => {
+        $crate::merge_t!(@ ($($a)::+), ($($b)::+), __MERGE; 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63)
+    };
+    (@ ($a:ty), ($b:ty), $op:ident;) => {
+        $crate::__tuplez::Unit
+    };
+    (@ ($a:ty), ($b:ty), $op:ident; $i:literal $($rest:literal)*) => {
+        <($a, $b) as $crate::__SetCons<
+            { <$a as $crate::__SetOps<$b>>::$op[$i] },
+            $crate::merge_t!(@ ($a), ($b), $op; $($rest)*),
+        >>::Output
+    };
+}
+
+/// Get an anonymous struct type consisting of the fields of an anonymous struct type
+/// whose names are also the names of fields of another anonymous struct type.
+///
+/// The fields keep their types in the first struct type, the types of the fields of the second one do not matter.
+/// Like [`merge_t!`], the struct types must be named by paths, usually type aliases declared with [`stru_t!`],
+/// each of them can have at most 64 fields, and it is not an `Intersect<A, B>` type operator:
+///
+/// ```
+/// use structz::*;
+///
+/// type User = stru_t! {
+///     id: u64,
+///     name: String,
+///     password: String,
+///     nickname?: String,
+/// };
+///
+/// type Form = stru_t! {
+///     name: &'static str,
+///     nickname: &'static str,
+///     captcha: &'static str,
+/// };
+///
+/// type Editable = intersect_t!(User, Form);
+///
+/// let editable: Editable = stru! { name: "John Doe".to_string(), .. };
+/// let _: stru_t! { name: String, nickname?: String } = editable;
+/// ```
+#[macro_export]
+macro_rules! intersect_t {
+    ($($a:ident)::+, $($b:ident)::+ $(,)?) => {
+        $crate::merge_t!(@ ($($a)::+), ($($b)::+), __INTERSECT; 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63)
+    };
+}
+
+/// Create anonymous struct object by giving some of the fields, the remaining fields take
+/// their default values declared by [`stru_t!`](crate::stru_t!#default-values).
+///
+/// The type of the struct is inferred from the context:
+///
+/// ```
+/// use structz::*;
+///
+/// type Options = stru_t! {
+///     port: u16 = 8080,
+///     host: String,
+///     verbose: bool,
+/// };
+///
+/// let options: Options = stru_default! {
+///     host: "localhost".into(),
+/// };
+/// assert_eq!(options, stru! { host: "localhost".to_string(), port: 8080, verbose: false });
+/// ```
+///
+/// Or precede the fields by the type:
+///
+/// ```
+/// use structz::*;
+///
+/// stru_t! {
+///     type Options = {
+///         port: u16 = 8080,
+///         host: String,
+///         verbose: bool,
+///     };
+/// }
+///
+/// let options = stru_default!(Options {
+///     host: "localhost".into(),
+/// });
+/// assert_eq!(options, stru! { host: "localhost".to_string(), port: 8080, verbose: false });
+/// ```
+///
+/// Anonymous struct types without declared default values fail the compilation rather than
+/// taking `Default::default()` silently:
+///
+/// ```compile_fail
+/// use structz::*;
+///
+/// type Options = stru_t! {
+///     port: u16,
+///     host: String,
+/// };
+///
+/// let options: Options = stru_default! {
+///     host: "localhost".into(),
+/// };
+/// ```
+///
+/// Use [`FillDefault`](crate::FillDefault) to fill the remaining fields with `Default::default()` instead.
+#[macro_export]
+macro_rules! stru_default {
+    ($ty:path { $($t:tt)* }) => {
+        <$ty as $crate::DeclaredDefaults<_>>::fill_defaults($crate::stru!($($t)*))
+    };
+    ($($t:tt)*) => {
+        <_ as $crate::DeclaredDefaults<_>>::fill_defaults($crate::stru!($($t)*))
+    };
+}
+
+/// Assert that two anonymous structs are equal, reporting only the differing fields on failure
+/// (requires `alloc` feature).
+///
+/// Unlike [`assert_eq!`], which prints the whole internal representation of both structs,
+/// the panic message lists the name and the `Debug` representation of both values of each
+/// field that differs. See also [`diff()`](crate::diff()).
+///
+/// ```
+/// use structz::*;
+///
+/// let person = stru! {
+///     name: "John",
+///     age: 26,
+/// };
+/// assert_stru_eq!(person, stru! { age: 26, name: "John" });
+/// ```
+///
+/// ```should_panic
+/// use structz::*;
+///
+/// let person = stru! {
+///     name: "John",
+///     age: 26,
+/// };
+/// // panics with:
+/// //
+/// // assertion `left == right` failed: wrong age
+/// // differing fields:
+/// //   age:
+/// //      left: 26
+/// //     right: 27
+/// assert_stru_eq!(person, stru! { age: 27, name: "John" }, "wrong {}", "age");
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[macro_export]
+macro_rules! assert_stru_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let diffs = $crate::diff(left, right);
+                if !diffs.is_empty() {
+                    $crate::__assert_stru_eq_failed(&diffs, ::core::option::Option::None);
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let diffs = $crate::diff(left, right);
+                if !diffs.is_empty() {
+                    $crate::__assert_stru_eq_failed(
+                        &diffs,
+                        ::core::option::Option::Some(::core::format_args!($($arg)+)),
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// Generate anonymous enum type, the sum-type counterpart of [`stru_t!`].
+///
+/// Each variant carries data of a type, use `()` for variants without data.
+/// Like fields of anonymous structs, variants are sorted by their names, so anonymous enums with
+/// the same variants but different variant orders are of the same type:
+///
+/// ```
+/// use structz::*;
+///
+/// type Outcome = enu_t! {
+///     ok: u32,
+///     not_found: (),
+///     failed: String,
+/// };
+///
+/// let outcome: enu_t! { failed: String, not_found: (), ok: u32 } = enu!(ok: 200);
+/// let _: Outcome = outcome;
+/// ```
+///
+/// Variants can also be named by string literals, just like fields of [`stru!`](crate::stru!).
+#[macro_export]
+macro_rules! enu_t {
+    ($($t:tt)*) => {
+        $crate::enu_t_inner!($crate; $($t)*)
+    };
+}
+
+/// Create anonymous enum object of a variant, the type of the enum is inferred from the context.
+///
+/// A variant without data can be created by its name alone.
+///
+/// ```
+/// use structz::*;
+///
+/// type Outcome = enu_t! {
+///     ok: u32,
+///     not_found: (),
+///     failed: String,
+/// };
+///
+/// fn fetch(id: u32) -> Outcome {
+///     match id {
+///         0 => enu!(not_found),
+///         1 => enu!(ok: 200),
+///         _ => enu!(failed: format!("bad id {id}")),
+///     }
+/// }
+///
+/// assert_eq!(fetch(1), enu!(ok: 200));
+/// assert_eq!(fetch(0).get_variant(), Some(&()) as Option<&()>);
+/// ```
+#[macro_export]
+macro_rules! enu {
+    ($v:ident) => {
+        $crate::enu!($v: ())
+    };
+    ($v:ident : $e:expr) => {
+        $crate::HasVariant::<$crate::ident!($v), _, _>::from_variant($e)
+    };
+    ($v:literal : $e:expr) => {
+        $crate::HasVariant::<$crate::__stringz::compact_string!($v), _, _>::from_variant($e)
+    };
+}
+
+/// Match an anonymous enum object by the names of its variants.
+///
+/// Each arm binds the data carried by a variant to an irrefutable pattern, or ignores it
+/// when only the name is given. The arms must be separated by commas.
+///
+/// ```
+/// use structz::*;
+///
+/// type Outcome = enu_t! {
+///     ok: u32,
+///     not_found: (),
+///     failed: String,
+/// };
+///
+/// fn describe(outcome: Outcome) -> String {
+///     match_enu!(outcome;
+///         ok(code) => format!("ok with {code}"),
+///         not_found => "not found".to_string(),
+///         failed(err) => format!("failed: {err}"),
+///     )
+/// }
+///
+/// assert_eq!(describe(enu!(ok: 204)), "ok with 204");
+/// assert_eq!(describe(enu!(not_found)), "not found");
+/// assert_eq!(describe(enu!(failed: "timeout".to_string())), "failed: timeout");
+/// ```
+///
+/// Matching is checked for exhaustiveness at compile time, use a `_` arm last to ignore the remaining variants:
+///
+/// ```compile_fail
+/// use structz::*;
+///
+/// let outcome: enu_t! { ok: u32, failed: String } = enu!(ok: 200);
+/// let code = match_enu!(outcome;
+///     ok(code) => code,
+/// );
+/// ```
+///
+/// ```
+/// use structz::*;
+///
+/// let outcome: enu_t! { ok: u32, failed: String } = enu!(ok: 200);
+/// let code = match_enu!(outcome;
+///     ok(code) => code,
+///     _ => 500,
+/// );
+/// assert_eq!(code, 200);
+/// ```
+#[macro_export]
+macro_rules! match_enu {
+    ($e:expr; $($arms:tt)*) => {{
+        let rest = $e;
+        $crate::match_enu!(@ rest; $($arms)*)
+    }};
+    (@ $r:ident; $(,)?) => {
+        $crate::__Exhausted::__absurd($r)
+    };
+    (@ $r:ident; _ => $body:expr $(,)?) => {{
+        let _ = $r;
+        $body
+    }};
+    (@ $r:ident; $v:ident => $body:expr $(, $($rest:tt)*)?) => {
+        $crate::match_enu!(@ $r; $v(_) => $body $(, $($rest)*)?)
+    };
+    (@ $r:ident; $v:literal => $body:expr $(, $($rest:tt)*)?) => {
+        $crate::match_enu!(@ $r; $v(_) => $body $(, $($rest)*)?)
+    };
+    (@ $r:ident; $v:ident ($p:pat) => $body:expr $(, $($rest:tt)*)?) => {
+        $crate::match_enu!(@ $r; $crate::ident!($v), $p => $body; $($($rest)*)?)
+    };
+    (@ $r:ident; $v:literal ($p:pat) => $body:expr $(, $($rest:tt)*)?) => {
+        $crate::match_enu!(@ $r; $crate::__stringz::compact_string!($v), $p => $body; $($($rest)*)?)
+    };
+    (@ $r:ident; $name:ty, $p:pat => $body:expr; $($rest:tt)*) => {
+        match $crate::HasVariant::<$name, _, _>::take_variant($r) {
+            ::core::result::Result::Ok(value) => {
+                let $p = value;
+                $body
+            }
+            ::core::result::Result::Err($r) => $crate::match_enu!(@ $r; $($rest)*),
+        }
     };
 }
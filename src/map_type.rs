@@ -0,0 +1,92 @@
+use crate::{TypedString, __Optional};
+use crate::__tuplez::{Tuple, Unit};
+
+/// A type-level function that maps the type of data carried by a field, see [`MapType`].
+pub trait TypeMapper<T> {
+    /// The mapped type.
+    type Output;
+}
+
+/// A trait for anonymous structs whose types of fields can be mapped by a [`TypeMapper`].
+pub trait MapFieldTypes<F> {
+    /// The anonymous struct with the types of fields mapped.
+    type Output;
+}
+
+/// Turn `stru_t! { a: A, b: B }` into `stru_t! { a: F::Output, b: F::Output }`, where `F` is a [`TypeMapper`].
+///
+/// ```
+/// use structz::*;
+///
+/// type User = stru_t! {
+///     name: String,
+///     age: u8,
+/// };
+///
+/// struct Validated;
+///
+/// impl<T> TypeMapper<T> for Validated {
+///     type Output = Result<T, &'static str>;
+/// }
+///
+/// let form: MapType<User, Validated> = stru! {
+///     name: Ok("John Doe".to_string()),
+///     age: Err("not a number"),
+/// };
+/// let _: stru_t! { name: Result<String, &'static str>, age: Result<u8, &'static str> } = form;
+/// ```
+///
+/// Optional fields stay optional, and `F` maps the type of data they carry if present:
+///
+/// ```
+/// use structz::*;
+///
+/// type User = stru_t! {
+///     name: String,
+///     nickname?: String,
+/// };
+///
+/// struct Many;
+///
+/// impl<T> TypeMapper<T> for Many {
+///     type Output = Vec<T>;
+/// }
+///
+/// let _: stru_t! { name: Vec<String>, nickname?: Vec<String> } = <MapType<User, Many>>::default();
+/// ```
+pub type MapType<S, F> = <S as MapFieldTypes<F>>::Output;
+
+impl<F> MapFieldTypes<F> for Unit {
+    type Output = Unit;
+}
+
+impl<Field, T, Other, F> MapFieldTypes<F> for Tuple<(Field, T), Other>
+where
+    (Field, T): __MapField<F>,
+    Other: MapFieldTypes<F>,
+{
+    type Output = Tuple<<(Field, T) as __MapField<F>>::Output, Other::Output>;
+}
+
+/// Helper trait used for [`MapFieldTypes`], which maps the type of data carried by a field,
+/// or by an optional field if it is present.
+#[doc(hidden)]
+pub trait __MapField<F> {
+    type Output;
+}
+
+impl<First, Other, T, F> __MapField<F> for (Tuple<First, Other>, T)
+where
+    Tuple<First, Other>: TypedString,
+    F: TypeMapper<T>,
+{
+    type Output = (Tuple<First, Other>, F::Output);
+}
+
+impl<Field, T, F> __MapField<F> for (__Optional<Field>, Option<T>)
+where
+    Field: TypedString,
+    F: TypeMapper<T>,
+{
+    type Output = (__Optional<Field>, Option<F::Output>);
+}
@@ -0,0 +1,184 @@
+use crate::__stringz::StrCmp;
+use crate::__tuplez::{Tuple, TupleLike, Unit};
+use crate::FieldAt;
+use core::cmp::Ordering;
+
+/// The maximum number of fields of the anonymous struct types merged or intersected
+/// by [`merge_t!`](crate::merge_t!) and [`intersect_t!`](crate::intersect_t!), and of the result.
+const MAX_FIELDS: usize = 64;
+
+/// Helper trait used for [`merge_t!`](crate::merge_t!) and [`intersect_t!`](crate::intersect_t!) macros,
+/// which compares the typed name `Name` with the name of each field of the anonymous struct type.
+#[doc(hidden)]
+pub trait __CmpNames<Name> {
+    const __CMP: [Ordering; MAX_FIELDS];
+}
+
+impl<Name> __CmpNames<Name> for Unit {
+    const __CMP: [Ordering; MAX_FIELDS] = [Ordering::Equal; MAX_FIELDS];
+}
+
+impl<Name, Field, T, Other> __CmpNames<Name> for Tuple<(Field, T), Other>
+where
+    Name: StrCmp<Field>,
+    Other: __CmpNames<Name>,
+{
+    const __CMP: [Ordering; MAX_FIELDS] = {
+        let mut cmp = [<Name as StrCmp<Field>>::CMP; MAX_FIELDS];
+        let mut i = 1;
+        while i < MAX_FIELDS {
+            cmp[i] = Other::__CMP[i - 1];
+            i += 1;
+        }
+        cmp
+    };
+}
+
+/// Helper trait used for [`merge_t!`](crate::merge_t!) and [`intersect_t!`](crate::intersect_t!) macros,
+/// which compares the name of each field of the anonymous struct type with the names of the fields of `S`.
+#[doc(hidden)]
+pub trait __CmpFields<S> {
+    const __CMP: [[Ordering; MAX_FIELDS]; MAX_FIELDS];
+}
+
+impl<S> __CmpFields<S> for Unit {
+    const __CMP: [[Ordering; MAX_FIELDS]; MAX_FIELDS] = [[Ordering::Equal; MAX_FIELDS]; MAX_FIELDS];
+}
+
+impl<Name, T, Other, S> __CmpFields<S> for Tuple<(Name, T), Other>
+where
+    S: __CmpNames<Name>,
+    Other: __CmpFields<S>,
+{
+    const __CMP: [[Ordering; MAX_FIELDS]; MAX_FIELDS] = {
+        let mut cmp = [S::__CMP; MAX_FIELDS];
+        let mut i = 1;
+        while i < MAX_FIELDS {
+            cmp[i] = Other::__CMP[i - 1];
+            i += 1;
+        }
+        cmp
+    };
+}
+
+/// Helper trait used for [`merge_t!`](crate::merge_t!) and [`intersect_t!`](crate::intersect_t!) macros,
+/// which finds the positions of the fields of the result in constant evaluation.
+///
+/// A position `i` below 64 is the `i`-th field of the anonymous struct type, a position `64 + j` is the
+/// `j`-th field of `S`, and the positions after the last field of the result are `usize::MAX`.
+#[doc(hidden)]
+pub trait __SetOps<S> {
+    const __MERGE: [usize; MAX_FIELDS];
+
+    const __INTERSECT: [usize; MAX_FIELDS];
+}
+
+impl<A, B> __SetOps<B> for A
+where
+    A: TupleLike + __CmpFields<B>,
+    B: TupleLike,
+{
+    const __MERGE: [usize; MAX_FIELDS] = {
+        if A::LEN > MAX_FIELDS || B::LEN > MAX_FIELDS {
+            panic!("cannot merge anonymous structs of more than 64 fields");
+        }
+        let mut positions = [usize::MAX; MAX_FIELDS];
+        let (mut i, mut j, mut n) = (0, 0, 0);
+        while i < A::LEN || j < B::LEN {
+            if n == MAX_FIELDS {
+                panic!("cannot merge anonymous structs into more than 64 fields");
+            }
+            let cmp = if i == A::LEN {
+                Ordering::Greater
+            } else if j == B::LEN {
+                Ordering::Less
+            } else {
+                A::__CMP[i][j]
+            };
+            match cmp {
+                Ordering::Less => {
+                    positions[n] = i;
+                    i += 1;
+                }
+                Ordering::Greater => {
+                    positions[n] = MAX_FIELDS + j;
+                    j += 1;
+                }
+                Ordering::Equal => {
+                    positions[n] = MAX_FIELDS + j;
+                    i += 1;
+                    j += 1;
+                }
+            }
+            n += 1;
+        }
+        positions
+    };
+
+    const __INTERSECT: [usize; MAX_FIELDS] = {
+        if A::LEN > MAX_FIELDS || B::LEN > MAX_FIELDS {
+            panic!("cannot intersect anonymous structs of more than 64 fields");
+        }
+        let mut positions = [usize::MAX; MAX_FIELDS];
+        let (mut i, mut j, mut n) = (0, 0, 0);
+        while i < A::LEN && j < B::LEN {
+            match A::__CMP[i][j] {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    positions[n] = i;
+                    i += 1;
+                    j += 1;
+                    n += 1;
+                }
+            }
+        }
+        positions
+    };
+}
+
+/// Helper trait used for [`merge_t!`](crate::merge_t!) and [`intersect_t!`](crate::intersect_t!) macros,
+/// which prepends the field at a position found by [`__SetOps`] to the anonymous struct type `Rest`.
+///
+/// It is implemented for a pair `(A, B)` of anonymous struct types, and prepends nothing at `usize::MAX`.
+#[doc(hidden)]
+pub trait __SetCons<const N: usize, Rest> {
+    type Output;
+}
+
+impl<A, B, Rest> __SetCons<{ usize::MAX }, Rest> for (A, B) {
+    type Output = Rest;
+}
+
+macro_rules! impl_set_cons {
+    ($side:ident: $($n:literal => $at:literal),* $(,)?) => {$(
+        impl<A, B, Rest> __SetCons<$n, Rest> for (A, B)
+        where
+            $side: FieldAt<$at>,
+        {
+            type Output = Tuple<(<$side as FieldAt<$at>>::Name, <$side as FieldAt<$at>>::Type), Rest>;
+        }
+    )*};
+}
+
+impl_set_cons! { A:
+    0 => 0, 1 => 1, 2 => 2, 3 => 3, 4 => 4, 5 => 5, 6 => 6, 7 => 7,
+    8 => 8, 9 => 9, 10 => 10, 11 => 11, 12 => 12, 13 => 13, 14 => 14, 15 => 15,
+    16 => 16, 17 => 17, 18 => 18, 19 => 19, 20 => 20, 21 => 21, 22 => 22, 23 => 23,
+    24 => 24, 25 => 25, 26 => 26, 27 => 27, 28 => 28, 29 => 29, 30 => 30, 31 => 31,
+    32 => 32, 33 => 33, 34 => 34, 35 => 35, 36 => 36, 37 => 37, 38 => 38, 39 => 39,
+    40 => 40, 41 => 41, 42 => 42, 43 => 43, 44 => 44, 45 => 45, 46 => 46, 47 => 47,
+    48 => 48, 49 => 49, 50 => 50, 51 => 51, 52 => 52, 53 => 53, 54 => 54, 55 => 55,
+    56 => 56, 57 => 57, 58 => 58, 59 => 59, 60 => 60, 61 => 61, 62 => 62, 63 => 63,
+}
+
+impl_set_cons! { B:
+    64 => 0, 65 => 1, 66 => 2, 67 => 3, 68 => 4, 69 => 5, 70 => 6, 71 => 7,
+    72 => 8, 73 => 9, 74 => 10, 75 => 11, 76 => 12, 77 => 13, 78 => 14, 79 => 15,
+    80 => 16, 81 => 17, 82 => 18, 83 => 19, 84 => 20, 85 => 21, 86 => 22, 87 => 23,
+    88 => 24, 89 => 25, 90 => 26, 91 => 27, 92 => 28, 93 => 29, 94 => 30, 95 => 31,
+    96 => 32, 97 => 33, 98 => 34, 99 => 35, 100 => 36, 101 => 37, 102 => 38, 103 => 39,
+    104 => 40, 105 => 41, 106 => 42, 107 => 43, 108 => 44, 109 => 45, 110 => 46, 111 => 47,
+    112 => 48, 113 => 49, 114 => 50, 115 => 51, 116 => 52, 117 => 53, 118 => 54, 119 => 55,
+    120 => 56, 121 => 57, 122 => 58, 123 => 59, 124 => 60, 125 => 61, 126 => 62, 127 => 63,
+}
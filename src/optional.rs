@@ -0,0 +1,506 @@
+use crate::TypedString;
+use crate::__stringz::Tagged;
+use crate::__tuplez::{
+    search::{Complete, Search, Used},
+    Tuple, Unit,
+};
+use crate::HasField;
+use core::marker::PhantomData;
+
+/// Type used to tag the typed field names of optional fields.
+#[doc(hidden)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct __OptionalTag;
+
+/// The typed field name of an optional field, which is the typed field name tagged as optional.
+///
+/// It has the same value as the typed field name, so the names of optional fields are reported
+/// as they are written, yet never clash with required fields.
+#[doc(hidden)]
+pub type __Optional<Field> = Tagged<__OptionalTag, Field>;
+
+/// Type used to indicate that an optional field is found by its plain name.
+#[doc(hidden)]
+pub struct Present<R, I>(PhantomData<(R, I)>);
+
+/// Type used to indicate that an optional field is found by its plain name in a struct of
+/// immutable references.
+#[doc(hidden)]
+pub struct PresentRef<T, R>(PhantomData<(T, R)>);
+
+/// Type used to indicate that an optional field is found by its plain name in a struct of
+/// mutable references.
+#[doc(hidden)]
+pub struct PresentMut<T, R>(PhantomData<(T, R)>);
+
+/// Type used to indicate that an optional field is absent.
+#[doc(hidden)]
+pub struct Absent<I>(PhantomData<I>);
+
+/// A trait for anonymous structs that can be built from their required fields,
+/// with the omitted optional fields absent.
+///
+/// Optional fields are declared as `name?: T` in [`stru_t!`], and carry an `Option<T>`.
+/// An optional field can be given either by its plain name with a value of type `T`,
+/// or by `name?` with a value of type `Option<T>`.
+///
+/// # Generic parameters
+///
+/// * `Given`: The anonymous struct consisting of the given fields.
+/// * `I`: Type used to indicate the positions of the given fields in the struct.
+///   Usually automatically inferred by Rust.
+///
+/// # Example
+///
+/// Usually used through the `..` syntax of [`stru!`]:
+///
+/// ```
+/// use structz::*;
+///
+/// type User = stru_t! {
+///     name: &'static str,
+///     nickname?: &'static str,
+/// };
+///
+/// let user: User = stru! { name: "John", .. };
+/// assert_eq!(field!(&user.nickname), None);
+///
+/// let user: User = stru! { name: "John", nickname: "Johnny", .. };
+/// assert_eq!(field!(&user.nickname), Some(&"Johnny"));
+///
+/// let user = User::fill_optional(stru! { name: "John", nickname?: None });
+/// assert_eq!(field!(user.nickname), None);
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "cannot build the anonymous struct from the given fields",
+    label = "a required field is missing, or a given field is unknown",
+    note = "every required field must be given, and optional fields may be omitted"
+)]
+pub trait FillOptional<Given, I> {
+    /// Build the anonymous struct from the given fields, the omitted optional fields are absent.
+    fn fill_optional(given: Given) -> Self;
+}
+
+impl FillOptional<Unit, Complete> for Unit {
+    fn fill_optional(_: Unit) -> Self {
+        Unit
+    }
+}
+
+impl<Field, T, Other, Given, R, I> FillOptional<Given, Used<(R, I)>> for Tuple<(Field, T), Other>
+where
+    Field: TypedString,
+    Given: Search<(Field, T), R>,
+    Other: FillOptional<Given::TakeRemainder, I>,
+{
+    fn fill_optional(given: Given) -> Self {
+        let (first, remainder) = Search::take(given);
+        Tuple(first, Other::fill_optional(remainder))
+    }
+}
+
+impl<Field, T, Other, Given, R, I> FillOptional<Given, Present<R, I>>
+    for Tuple<(__Optional<Field>, Option<T>), Other>
+where
+    Field: TypedString,
+    Given: Search<(Field, T), R>,
+    Other: FillOptional<Given::TakeRemainder, I>,
+{
+    fn fill_optional(given: Given) -> Self {
+        let ((field, value), remainder) = Search::take(given);
+        Tuple(
+            (Tagged(__OptionalTag, field), Some(value)),
+            Other::fill_optional(remainder),
+        )
+    }
+}
+
+impl<Field, T, Other, Given, I> FillOptional<Given, Absent<I>>
+    for Tuple<(__Optional<Field>, Option<T>), Other>
+where
+    Field: TypedString + Default,
+    Other: FillOptional<Given, I>,
+{
+    fn fill_optional(given: Given) -> Self {
+        Tuple(
+            (Default::default(), None),
+            Other::fill_optional(given),
+        )
+    }
+}
+
+/// Helper trait used for [`field!`] and the other field macros, which checks that a field can be
+/// accessed by its plain name, whether it is required or optional.
+///
+/// Optional fields are also accessed in the structs of references made by [`as_ref!`] and [`as_mut!`],
+/// where they yield an `Option` of reference like [`field!`] on a borrowed struct.
+///
+/// The macros report missing fields through a trait declared for each access, which names
+/// the field as it is written. The access itself is done by [`__FieldOps`] on the position `R`,
+/// which is not known for missing fields, so that they are reported only once.
+#[doc(hidden)]
+pub trait __FieldAccess<Field, R> {}
+
+/// Helper trait used for [`replace_field!`], [`set_field!`] and [`update_field!`] macros,
+/// which checks that a field can be written by its plain name, whether it is required or optional.
+#[doc(hidden)]
+pub trait __FieldUpdate<Field, R>: __FieldAccess<Field, R> {}
+
+impl<S, Field, T, R> __FieldAccess<Field, (T, R)> for S
+where
+    Field: TypedString,
+    S: HasField<Field, T, R>,
+{
+}
+
+impl<S, Field, T, R> __FieldUpdate<Field, (T, R)> for S
+where
+    Field: TypedString,
+    S: HasField<Field, T, R>,
+{
+}
+
+impl<S, Field, T, R> __FieldAccess<Field, Present<T, R>> for S
+where
+    Field: TypedString,
+    S: HasField<__Optional<Field>, Option<T>, R>,
+{
+}
+
+impl<S, Field, T, R> __FieldUpdate<Field, Present<T, R>> for S
+where
+    Field: TypedString,
+    S: HasField<__Optional<Field>, Option<T>, R>,
+{
+}
+
+impl<'b, S, Field, T, R> __FieldAccess<Field, PresentRef<&'b T, R>> for S
+where
+    Field: TypedString,
+    S: HasField<__Optional<Field>, &'b Option<T>, R>,
+{
+}
+
+impl<'b, S, Field, T, R> __FieldAccess<Field, PresentMut<&'b mut T, R>> for S
+where
+    Field: TypedString,
+    S: HasField<__Optional<Field>, &'b mut Option<T>, R>,
+{
+}
+
+/// Helper trait used for [`field!`] and the other field macros, which accesses the field found
+/// by [`__FieldAccess`] at the position indicated by `Self`.
+#[doc(hidden)]
+pub trait __FieldOps<S, Field> {
+    type Value;
+
+    type Rest;
+
+    type Ref<'a>
+    where
+        S: 'a,
+        Field: 'a,
+        Self: 'a;
+
+    type Mut<'a>
+    where
+        S: 'a,
+        Field: 'a,
+        Self: 'a;
+
+    fn __take_field(s: S) -> Self::Value;
+
+    fn __take_field_rest(s: S) -> (Self::Value, Self::Rest);
+
+    fn __get_field<'a>(s: &'a S) -> Self::Ref<'a>
+    where
+        Field: 'a,
+        Self: 'a;
+
+    fn __get_field_mut<'a>(s: &'a mut S) -> Self::Mut<'a>
+    where
+        Field: 'a,
+        Self: 'a;
+}
+
+/// Helper trait used for [`replace_field!`], [`set_field!`] and [`update_field!`] macros,
+/// which writes the field found by [`__FieldUpdate`] at the position indicated by `Self`.
+///
+/// Optional fields stay optional: they are written with `Option`s, and the value `U` they are
+/// mapped to is wrapped in an `Option` too.
+#[doc(hidden)]
+pub trait __FieldUpdateOps<S, Field>: __FieldOps<S, Field> {
+    type MapValue<U>;
+
+    type MapOutput<U>;
+
+    fn __replace_field(s: &mut S, value: Self::Value) -> Self::Value;
+
+    fn __map_field<U, F>(s: S, f: F) -> Self::MapOutput<U>
+    where
+        F: FnOnce(Self::Value) -> Self::MapValue<U>;
+}
+
+impl<S, Field, T, R> __FieldOps<S, Field> for (T, R)
+where
+    Field: TypedString,
+    S: HasField<Field, T, R>,
+{
+    type Value = T;
+
+    type Rest = S::Rest;
+
+    type Ref<'a>
+        = &'a T
+    where
+        S: 'a,
+        Field: 'a,
+        Self: 'a;
+
+    type Mut<'a>
+        = &'a mut T
+    where
+        S: 'a,
+        Field: 'a,
+        Self: 'a;
+
+    fn __take_field(s: S) -> Self::Value {
+        s.take_field()
+    }
+
+    fn __take_field_rest(s: S) -> (Self::Value, Self::Rest) {
+        s.take_field_rest()
+    }
+
+    fn __get_field<'a>(s: &'a S) -> Self::Ref<'a>
+    where
+        Field: 'a,
+        Self: 'a,
+    {
+        s.get_field()
+    }
+
+    fn __get_field_mut<'a>(s: &'a mut S) -> Self::Mut<'a>
+    where
+        Field: 'a,
+        Self: 'a,
+    {
+        s.get_field_mut()
+    }
+}
+
+impl<S, Field, T, R> __FieldUpdateOps<S, Field> for (T, R)
+where
+    Field: TypedString,
+    S: HasField<Field, T, R>,
+{
+    type MapValue<U> = U;
+
+    type MapOutput<U> = S::MapFieldOutput<U>;
+
+    fn __replace_field(s: &mut S, value: Self::Value) -> Self::Value {
+        s.replace_field(value)
+    }
+
+    fn __map_field<U, F>(s: S, f: F) -> Self::MapOutput<U>
+    where
+        F: FnOnce(Self::Value) -> Self::MapValue<U>,
+    {
+        s.map_field(f)
+    }
+}
+
+impl<S, Field, T, R> __FieldOps<S, Field> for Present<T, R>
+where
+    Field: TypedString,
+    S: HasField<__Optional<Field>, Option<T>, R>,
+{
+    type Value = Option<T>;
+
+    type Rest = S::Rest;
+
+    type Ref<'a>
+        = Option<&'a T>
+    where
+        S: 'a,
+        Field: 'a,
+        Self: 'a;
+
+    type Mut<'a>
+        = Option<&'a mut T>
+    where
+        S: 'a,
+        Field: 'a,
+        Self: 'a;
+
+    fn __take_field(s: S) -> Self::Value {
+        s.take_field()
+    }
+
+    fn __take_field_rest(s: S) -> (Self::Value, Self::Rest) {
+        s.take_field_rest()
+    }
+
+    fn __get_field<'a>(s: &'a S) -> Self::Ref<'a>
+    where
+        Field: 'a,
+        Self: 'a,
+    {
+        s.get_field().as_ref()
+    }
+
+    fn __get_field_mut<'a>(s: &'a mut S) -> Self::Mut<'a>
+    where
+        Field: 'a,
+        Self: 'a,
+    {
+        s.get_field_mut().as_mut()
+    }
+}
+
+impl<S, Field, T, R> __FieldUpdateOps<S, Field> for Present<T, R>
+where
+    Field: TypedString,
+    S: HasField<__Optional<Field>, Option<T>, R>,
+{
+    type MapValue<U> = Option<U>;
+
+    type MapOutput<U> = S::MapFieldOutput<Option<U>>;
+
+    fn __replace_field(s: &mut S, value: Self::Value) -> Self::Value {
+        s.replace_field(value)
+    }
+
+    fn __map_field<U, F>(s: S, f: F) -> Self::MapOutput<U>
+    where
+        F: FnOnce(Self::Value) -> Self::MapValue<U>,
+    {
+        s.map_field(f)
+    }
+}
+
+impl<'b, S, Field, T, R> __FieldOps<S, Field> for PresentRef<&'b T, R>
+where
+    Field: TypedString,
+    S: HasField<__Optional<Field>, &'b Option<T>, R>,
+{
+    type Value = Option<&'b T>;
+
+    type Rest = S::Rest;
+
+    type Ref<'a>
+        = Option<&'b T>
+    where
+        S: 'a,
+        Field: 'a,
+        Self: 'a;
+
+    type Mut<'a>
+        = Option<&'b T>
+    where
+        S: 'a,
+        Field: 'a,
+        Self: 'a;
+
+    fn __take_field(s: S) -> Self::Value {
+        s.take_field().as_ref()
+    }
+
+    fn __take_field_rest(s: S) -> (Self::Value, Self::Rest) {
+        let (value, rest) = s.take_field_rest();
+        (value.as_ref(), rest)
+    }
+
+    fn __get_field<'a>(s: &'a S) -> Self::Ref<'a>
+    where
+        Field: 'a,
+        Self: 'a,
+    {
+        s.get_field().as_ref()
+    }
+
+    fn __get_field_mut<'a>(s: &'a mut S) -> Self::Mut<'a>
+    where
+        Field: 'a,
+        Self: 'a,
+    {
+        s.get_field().as_ref()
+    }
+}
+
+impl<'b, S, Field, T, R> __FieldOps<S, Field> for PresentMut<&'b mut T, R>
+where
+    Field: TypedString,
+    S: HasField<__Optional<Field>, &'b mut Option<T>, R>,
+{
+    type Value = Option<&'b mut T>;
+
+    type Rest = S::Rest;
+
+    type Ref<'a>
+        = Option<&'a T>
+    where
+        S: 'a,
+        Field: 'a,
+        Self: 'a;
+
+    type Mut<'a>
+        = Option<&'a mut T>
+    where
+        S: 'a,
+        Field: 'a,
+        Self: 'a;
+
+    fn __take_field(s: S) -> Self::Value {
+        s.take_field().as_mut()
+    }
+
+    fn __take_field_rest(s: S) -> (Self::Value, Self::Rest) {
+        let (value, rest) = s.take_field_rest();
+        (value.as_mut(), rest)
+    }
+
+    fn __get_field<'a>(s: &'a S) -> Self::Ref<'a>
+    where
+        Field: 'a,
+        Self: 'a,
+    {
+        s.get_field().as_ref()
+    }
+
+    fn __get_field_mut<'a>(s: &'a mut S) -> Self::Mut<'a>
+    where
+        Field: 'a,
+        Self: 'a,
+    {
+        s.get_field_mut().as_mut()
+    }
+}
+
+/// Helper trait used for the conversions that unwrap the values of optional fields,
+/// which gets the plain typed field name of an optional field.
+#[doc(hidden)]
+pub trait __PlainName {
+    type Plain: TypedString;
+
+    fn __plain_name(self) -> Self::Plain;
+}
+
+impl<First, Other> __PlainName for Tuple<First, Other>
+where
+    Self: TypedString,
+{
+    type Plain = Self;
+
+    fn __plain_name(self) -> Self::Plain {
+        self
+    }
+}
+
+impl<Field: TypedString> __PlainName for __Optional<Field> {
+    type Plain = Field;
+
+    fn __plain_name(self) -> Self::Plain {
+        self.1
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{TypedString, __Optional};
+use crate::__tuplez::{Tuple, Unit};
+
+/// A trait for anonymous structs that have a partial counterpart.
+///
+/// The partial counterpart of an anonymous struct has the same fields, but the type of each
+/// field is wrapped in an `Option`. See [`Partial`].
+pub trait ToPartial {
+    /// The partial counterpart of the anonymous struct.
+    type Partial;
+
+    /// Convert the anonymous struct to its partial counterpart, every field of which is `Some`.
+    fn to_partial(self) -> Self::Partial;
+
+    /// Overwrite the fields of the anonymous struct with the fields of `partial` that are `Some`.
+    fn patch(&mut self, partial: Self::Partial);
+}
+
+/// Turn `stru_t! { a: A, b: B }` into `stru_t! { a: Option<A>, b: Option<B> }`.
+///
+/// A partial struct is useful when only some of the fields are known, for example, the values
+/// collected from a single configuration source. It implements `Default` and defaults to
+/// the struct of which all fields are `None`.
+///
+/// ```
+/// use structz::*;
+///
+/// type Config = stru_t! {
+///     host: &'static str,
+///     port: u16,
+/// };
+///
+/// let partial: Partial<Config> = stru! {
+///     host: None,
+///     port: Some(8080),
+/// };
+/// let _: stru_t! { host: Option<&'static str>, port: Option<u16> } = partial;
+///
+/// assert_eq!(Partial::<Config>::default(), stru! { host: None, port: None });
+///
+/// let config: Config = stru! { host: "localhost", port: 80 };
+/// assert_eq!(config.to_partial(), stru! { host: Some("localhost"), port: Some(80) });
+/// ```
+pub type Partial<S> = <S as ToPartial>::Partial;
+
+impl ToPartial for Unit {
+    type Partial = Unit;
+
+    fn to_partial(self) -> Self::Partial {
+        Unit
+    }
+
+    fn patch(&mut self, _: Self::Partial) {}
+}
+
+impl<Field, T, Other> ToPartial for Tuple<(Field, T), Other>
+where
+    Field: TypedString,
+    Other: ToPartial,
+{
+    type Partial = Tuple<(Field, Option<T>), Other::Partial>;
+
+    fn to_partial(self) -> Self::Partial {
+        let Tuple((field, value), other) = self;
+        Tuple((field, Some(value)), other.to_partial())
+    }
+
+    fn patch(&mut self, partial: Self::Partial) {
+        let Tuple((_, value), other) = partial;
+        if let Some(value) = value {
+            self.0 .1 = value;
+        }
+        self.1.patch(other);
+    }
+}
+
+/// A trait for partial anonymous structs that can be layered on top of each other.
+///
+/// See [`merge_partial()`].
+pub trait MergePartial {
+    /// Merge two partial structs, the fields of `overlay` that are `Some` take precedence.
+    fn merge_partial(self, overlay: Self) -> Self;
+}
+
+impl MergePartial for Unit {
+    fn merge_partial(self, _: Self) -> Self {
+        Unit
+    }
+}
+
+impl<Field, T, Other> MergePartial for Tuple<(Field, Option<T>), Other>
+where
+    Field: TypedString,
+    Other: MergePartial,
+{
+    fn merge_partial(self, overlay: Self) -> Self {
+        let Tuple((field, base), base_other) = self;
+        let Tuple((_, overlay), overlay_other) = overlay;
+        Tuple(
+            (field, overlay.or(base)),
+            base_other.merge_partial(overlay_other),
+        )
+    }
+}
+
+/// A trait for anonymous structs that have a required counterpart, see [`Required`].
+pub trait ToRequired {
+    /// The required counterpart of the anonymous struct.
+    type Required;
+
+    /// Convert the anonymous struct to its required counterpart,
+    /// returns `None` if any optional field is absent.
+    fn to_required(self) -> Option<Self::Required>;
+}
+
+/// Turn `stru_t! { a: A, b?: B }` into `stru_t! { a: A, b: B }`, i.e. make the optional fields
+/// required, like `Required` of TypeScript.
+///
+/// ```
+/// use structz::*;
+///
+/// type User = stru_t! {
+///     name: &'static str,
+///     nickname?: &'static str,
+/// };
+///
+/// let _: Required<User> = stru! { name: "John", nickname: "Johnny" };
+///
+/// let user: User = stru! { name: "John", nickname: "Johnny", .. };
+/// assert_eq!(user.to_required(), Some(stru! { name: "John", nickname: "Johnny" }));
+///
+/// let user: User = stru! { name: "John", .. };
+/// assert_eq!(user.to_required(), None);
+/// ```
+///
+/// The types of the fields are kept, so the fields of a [`Partial`] struct stay `Option`s.
+/// Use [`transpose_option()`](crate::TransposeOption::transpose_option) to unwrap them:
+///
+/// ```
+/// use structz::*;
+///
+/// type Config = stru_t! {
+///     host: &'static str,
+///     port: u16,
+/// };
+///
+/// let partial: Required<Partial<Config>> = stru! { host: Some("localhost"), port: Some(80) };
+/// let config: Config = partial.transpose_option().unwrap();
+/// assert_eq!(config, stru! { host: "localhost", port: 80 });
+/// ```
+pub type Required<S> = <S as ToRequired>::Required;
+
+impl ToRequired for Unit {
+    type Required = Unit;
+
+    fn to_required(self) -> Option<Self::Required> {
+        Some(Unit)
+    }
+}
+
+impl<Field, T, Other> ToRequired for Tuple<(Field, T), Other>
+where
+    (Field, T): __RequiredField,
+    Other: ToRequired,
+{
+    type Required = Tuple<<(Field, T) as __RequiredField>::Output, Other::Required>;
+
+    fn to_required(self) -> Option<Self::Required> {
+        let Tuple(first, other) = self;
+        Some(Tuple(first.__to_required()?, other.to_required()?))
+    }
+}
+
+/// Helper trait used for [`ToRequired`], which makes an optional field required,
+/// and keeps a required field as it is.
+#[doc(hidden)]
+pub trait __RequiredField {
+    type Output;
+
+    fn __to_required(self) -> Option<Self::Output>;
+}
+
+impl<First, Other, T> __RequiredField for (Tuple<First, Other>, T)
+where
+    Tuple<First, Other>: TypedString,
+{
+    type Output = Self;
+
+    fn __to_required(self) -> Option<Self::Output> {
+        Some(self)
+    }
+}
+
+impl<Field, T> __RequiredField for (__Optional<Field>, Option<T>)
+where
+    Field: TypedString,
+{
+    type Output = (Field, T);
+
+    fn __to_required(self) -> Option<Self::Output> {
+        let (field, value) = self;
+        Some((field.1, value?))
+    }
+}
+
+/// Overwrite the fields of `full` with the fields of `partial` that are `Some`.
+///
+/// ```
+/// use structz::*;
+///
+/// type Config = stru_t! {
+///     host: &'static str,
+///     port: u16,
+///     verbose: bool,
+/// };
+///
+/// let mut config: Config = stru! {
+///     host: "localhost",
+///     port: 80,
+///     verbose: false,
+/// };
+/// patch(&mut config, stru! {
+///     host: None,
+///     port: Some(8080),
+///     verbose: Some(true),
+/// });
+/// assert_eq!(config, stru! { host: "localhost", port: 8080, verbose: true });
+/// ```
+pub fn patch<S>(full: &mut S, partial: S::Partial)
+where
+    S: ToPartial,
+{
+    full.patch(partial)
+}
+
+/// Layer a partial struct on top of another one, the fields of `overlay` that are `Some`
+/// take precedence over the fields of `base`.
+///
+/// Merging is associative, so layered configurations can be built up one source at a time:
+///
+/// ```
+/// use structz::*;
+///
+/// type Config = stru_t! {
+///     host: &'static str,
+///     port: u16,
+///     verbose: bool,
+/// };
+///
+/// let defaults: Config = stru! {
+///     host: "localhost",
+///     port: 80,
+///     verbose: false,
+/// };
+/// let file: Partial<Config> = stru! { host: Some("example.com"), port: Some(8080), verbose: None };
+/// let env: Partial<Config> = stru! { host: None, port: Some(8000), verbose: None };
+/// let cli: Partial<Config> = stru! { host: None, port: None, verbose: Some(true) };
+///
+/// let mut config = defaults;
+/// patch(&mut config, merge_partial(merge_partial(file, env), cli));
+/// assert_eq!(config, stru! { host: "example.com", port: 8000, verbose: true });
+/// ```
+pub fn merge_partial<P>(base: P, overlay: P) -> P
+where
+    P: MergePartial,
+{
+    base.merge_partial(overlay)
+}
@@ -0,0 +1,201 @@
+use crate::__tuplez::{Tuple, Unit};
+use crate::{TypedString, __FieldName, __PlainName};
+use core::fmt;
+
+/// A trait for anonymous structs whose fields are all `Option`s.
+///
+/// ```
+/// use structz::*;
+///
+/// let person = stru! {
+///     name: Some("John"),
+///     age: Some(26),
+/// };
+/// assert_eq!(person.transpose_option(), Some(stru! { name: "John", age: 26 }));
+///
+/// let person = stru! {
+///     name: Some("John"),
+///     age: None::<u8>,
+/// };
+/// assert_eq!(person.transpose_option(), None);
+/// ```
+///
+/// Optional fields carry `Option`s too, and become required fields:
+///
+/// ```
+/// use structz::*;
+///
+/// let user: stru_t! { name: Option<&str>, nickname?: &str } = stru! {
+///     name: Some("John"),
+///     nickname: "Johnny",
+///     ..
+/// };
+/// let user: stru_t! { name: &str, nickname: &str } = user.transpose_option().unwrap();
+/// assert_eq!(user, stru! { name: "John", nickname: "Johnny" });
+/// ```
+pub trait TransposeOption {
+    /// The anonymous struct that carries the values of the `Option`s.
+    type Output;
+
+    /// Convert a struct of `Option`s into an `Option` of struct,
+    /// which is `Some` only if all fields are `Some`.
+    fn transpose_option(self) -> Option<Self::Output>;
+
+    /// Convert a struct of `Option`s into a struct of values, or an iterator over the names
+    /// of all fields that are `None`, sorted lexicographically.
+    ///
+    /// ```
+    /// use structz::*;
+    ///
+    /// let person = stru! {
+    ///     name: None::<&str>,
+    ///     age: Some(26),
+    ///     tags: None::<Vec<&str>>,
+    /// };
+    /// let missing = person.transpose_option_or_missing().unwrap_err();
+    /// assert!(missing.eq(["name", "tags"]));
+    /// ```
+    fn transpose_option_or_missing(self) -> Result<Self::Output, MissingFields<Self>>
+    where
+        Self: Sized,
+    {
+        if self.__missing_field(0).is_some() {
+            return Err(MissingFields {
+                fields: self,
+                next: 0,
+            });
+        }
+        match self.transpose_option() {
+            Some(output) => Ok(output),
+            None => unreachable!(),
+        }
+    }
+
+    /// The name of the `n`-th field that is `None`.
+    #[doc(hidden)]
+    fn __missing_field(&self, n: usize) -> Option<&'static str>;
+}
+
+impl TransposeOption for Unit {
+    type Output = Unit;
+
+    fn transpose_option(self) -> Option<Self::Output> {
+        Some(Unit)
+    }
+
+    fn __missing_field(&self, _: usize) -> Option<&'static str> {
+        None
+    }
+}
+
+impl<Field, T, Other> TransposeOption for Tuple<(Field, Option<T>), Other>
+where
+    Field: __PlainName + __FieldName,
+    Other: TransposeOption,
+{
+    type Output = Tuple<(Field::Plain, T), Other::Output>;
+
+    fn transpose_option(self) -> Option<Self::Output> {
+        let Tuple((field, value), other) = self;
+        Some(Tuple(
+            (field.__plain_name(), value?),
+            other.transpose_option()?,
+        ))
+    }
+
+    fn __missing_field(&self, n: usize) -> Option<&'static str> {
+        match (&self.0 .1, n) {
+            (Some(_), n) => self.1.__missing_field(n),
+            (None, 0) => Some(Field::__NAME),
+            (None, n) => self.1.__missing_field(n - 1),
+        }
+    }
+}
+
+/// An iterator over the names of the fields that are `None`, and the struct of `Option`s itself.
+///
+/// Returned by [`transpose_option_or_missing()`](TransposeOption::transpose_option_or_missing).
+pub struct MissingFields<S> {
+    fields: S,
+    next: usize,
+}
+
+impl<S> MissingFields<S> {
+    /// Get the struct of `Option`s back.
+    pub fn into_inner(self) -> S {
+        self.fields
+    }
+}
+
+impl<S: TransposeOption> Iterator for MissingFields<S> {
+    type Item = &'static str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let name = self.fields.__missing_field(self.next)?;
+        self.next += 1;
+        Some(name)
+    }
+}
+
+impl<S: TransposeOption> fmt::Debug for MissingFields<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        let mut n = self.next;
+        while let Some(name) = self.fields.__missing_field(n) {
+            list.entry(&name);
+            n += 1;
+        }
+        list.finish()
+    }
+}
+
+/// A trait for anonymous structs whose fields are all `Result`s with the same error type.
+///
+/// ```
+/// use structz::*;
+///
+/// let person = stru! {
+///     name: Ok("John"),
+///     age: "26".parse::<u8>(),
+/// };
+/// assert_eq!(person.transpose_result(), Ok(stru! { name: "John", age: 26 }));
+///
+/// let person = stru! {
+///     name: Ok("John"),
+///     age: "twenty-six".parse::<u8>(),
+/// };
+/// let (field, _) = person.transpose_result().unwrap_err();
+/// assert_eq!(field, "age");
+/// ```
+pub trait TransposeResult<E> {
+    /// The anonymous struct that carries the values of the `Ok`s.
+    type Output;
+
+    /// Convert a struct of `Result`s into a `Result` of struct.
+    ///
+    /// If some fields are `Err`, the name and the error of the first of them
+    /// (in lexicographic order of field names) are returned.
+    fn transpose_result(self) -> Result<Self::Output, (&'static str, E)>;
+}
+
+impl<E> TransposeResult<E> for Unit {
+    type Output = Unit;
+
+    fn transpose_result(self) -> Result<Self::Output, (&'static str, E)> {
+        Ok(Unit)
+    }
+}
+
+impl<Field, T, E, Other> TransposeResult<E> for Tuple<(Field, Result<T, E>), Other>
+where
+    Field: TypedString + __FieldName,
+    Other: TransposeResult<E>,
+{
+    type Output = Tuple<(Field, T), Other::Output>;
+
+    fn transpose_result(self) -> Result<Self::Output, (&'static str, E)> {
+        let Tuple((field, value), other) = self;
+        let value = value.map_err(|e| (Field::__NAME, e))?;
+        Ok(Tuple((field, value), other.transpose_result()?))
+    }
+}
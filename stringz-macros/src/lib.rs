@@ -1,5 +1,6 @@
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::ext::IdentExt;
 
 struct ReExportStringz<T: syn::parse::Parse> {
     pub path: syn::Path,
@@ -19,7 +20,7 @@ impl<T: syn::parse::Parse> syn::parse::Parse for ReExportStringz<T> {
 pub fn ident(input: TokenStream) -> TokenStream {
     let ReExportStringz { path, other: name } =
         syn::parse_macro_input!(input as ReExportStringz<syn::Ident>);
-    let name = name.to_string();
+    let name = name.unraw().to_string();
     let name: Vec<_> = name
         .chars()
         .map(|ch| quote!( #path::Character<#ch> ))
@@ -38,3 +39,33 @@ pub fn string(input: TokenStream) -> TokenStream {
         .collect();
     quote!( #path::__tuplez::tuple_t![ #( #name ),* ] ).into()
 }
+
+/// Pack the UTF-8 encoding of a string into chunks of 16 bytes.
+fn compact(path: &syn::Path, name: &str) -> proc_macro2::TokenStream {
+    let chunks: Vec<_> = name
+        .as_bytes()
+        .chunks(16)
+        .map(|chunk| {
+            let len = proc_macro2::Literal::u8_unsuffixed(chunk.len() as u8);
+            let mut bytes = [0; 16];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            let bytes = proc_macro2::Literal::u128_unsuffixed(u128::from_be_bytes(bytes));
+            quote!( #path::Chunk<#bytes, #len> )
+        })
+        .collect();
+    quote!( #path::__tuplez::tuple_t![ #( #chunks ),* ] )
+}
+
+#[proc_macro]
+pub fn compact_ident(input: TokenStream) -> TokenStream {
+    let ReExportStringz { path, other: name } =
+        syn::parse_macro_input!(input as ReExportStringz<syn::Ident>);
+    compact(&path, &name.unraw().to_string()).into()
+}
+
+#[proc_macro]
+pub fn compact_string(input: TokenStream) -> TokenStream {
+    let ReExportStringz { path, other: name } =
+        syn::parse_macro_input!(input as ReExportStringz<syn::LitStr>);
+    compact(&path, &name.value()).into()
+}
@@ -45,11 +45,38 @@
 //! use stringz::string;
 //! assert_eq!(std::mem::size_of::<string!("no matter how long it is")>(), 0);
 //! ```
+//!
+//! # Compact encoding
+//!
+//! One type per character makes long strings deeply nested types, which slow down type checking
+//! and bloat symbol names. The [`compact_string`] and [`compact_ident`] macros pack the UTF-8
+//! encoding of the string into chunks of 16 bytes instead. For example, `"no matter how long it is"`
+//! is split into `"no matter how lo"` and `"ng it is"`, which are converted to:
+//!
+//! ```text
+//! (Chunk<BYTES_1, 16>, Chunk<BYTES_2, 8>)
+//! ```
+//!
+//! where `BYTES_1` and `BYTES_2` are the `u128`s of the big-endian bytes of each chunk.
+//!
+//! Compact typed strings are zero-sized types too, but they are not of the same type as
+//! the typed strings of the per-character encoding:
+//!
+//! ```
+//! use stringz::{compact_string, TypedString};
+//!
+//! type Long = compact_string!("no matter how long it is");
+//! assert_eq!(std::mem::size_of::<Long>(), 0);
+//! assert_eq!(Long::value(), "no matter how long it is");
+//! ```
 
 extern crate self as stringz;
 
 #[doc(hidden)]
-pub use stringz_macros::{ident as ident_inner, string as string_inner};
+pub use stringz_macros::{
+    compact_ident as compact_ident_inner, compact_string as compact_string_inner,
+    ident as ident_inner, string as string_inner,
+};
 #[doc(hidden)]
 pub use tuplez as __tuplez;
 
@@ -86,6 +113,16 @@ macro_rules! string {
 ///
 /// test_hello::<ident!(hello)>();
 /// ```
+///
+/// Raw identifiers are converted without the `r#` prefix:
+///
+/// ```
+/// use stringz::{ident, string};
+///
+/// fn test_type() -> string!("type") {
+///     <ident!(r#type)>::default()
+/// }
+/// ```
 #[macro_export]
 macro_rules! ident {
     ($i:ident) => {
@@ -93,19 +130,141 @@ macro_rules! ident {
     };
 }
 
+/// Convert a string to a type in the compact encoding, the input must be a string literal.
+///
+/// See [compact encoding](crate#compact-encoding).
+///
+/// # Example
+///
+/// ```
+/// use stringz::{TypedString, compact_string};
+///
+/// fn test_hello<T: TypedString>() {
+///     assert_eq!(T::value(), "hello, compact world");
+/// }
+///
+/// test_hello::<compact_string!("hello, compact world")>();
+/// ```
+#[macro_export]
+macro_rules! compact_string {
+    ($s:literal) => {
+        $crate::compact_string_inner!($crate; $s)
+    };
+}
+
+/// Convert a string to a type in the compact encoding, the input must be an identifier.
+///
+/// See [compact encoding](crate#compact-encoding).
+///
+/// # Example
+///
+/// ```
+/// use stringz::{TypedString, compact_ident, compact_string};
+///
+/// fn test_hello<T: TypedString>() {
+///     assert_eq!(T::value(), "hello_compact_world");
+/// }
+///
+/// test_hello::<compact_ident!(hello_compact_world)>();
+///
+/// fn test_type() -> compact_string!("type") {
+///     <compact_ident!(r#type)>::default()
+/// }
+/// ```
+#[macro_export]
+macro_rules! compact_ident {
+    ($i:ident) => {
+        $crate::compact_ident_inner!($crate; $i)
+    };
+}
+
 #[cfg(all(not(feature = "std"), feature = "alloc"))]
 extern crate alloc;
 
+use core::cmp::Ordering;
+
 #[cfg(all(not(feature = "std"), feature = "alloc"))]
 use alloc::{
     format,
     string::{String, ToString},
+    vec::Vec,
 };
 
 /// Single `char` type value representation.
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Character<const C: char>;
 
+/// A chunk of up to 16 bytes of the UTF-8 encoding of a string, used by the
+/// [compact encoding](crate#compact-encoding).
+///
+/// `BYTES` holds the bytes in big-endian order, padded with zeros, and `LEN` is the number of bytes.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Chunk<const BYTES: u128, const LEN: u8>;
+
+/// A typed string of the same value as `S`, but of a different type for each `Tag`.
+///
+/// It keeps apart typed strings of the same value that mean different things,
+/// e.g. [structz](https://docs.rs/structz) marks the names of optional fields with it.
+///
+/// ```
+/// use core::cmp::Ordering;
+/// use stringz::*;
+///
+/// #[derive(Default)]
+/// struct Marked;
+///
+/// type Name = Tagged<Marked, compact_ident!(name)>;
+/// assert_eq!(<Name as StrCmp<compact_ident!(name)>>::CMP, Ordering::Equal);
+/// assert_eq!(<compact_ident!(name) as StrCmp<Name>>::CMP, Ordering::Equal);
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Tagged<Tag, S>(pub Tag, pub S);
+
+/// Helper trait used for typed strings in the compact encoding, which collects the UTF-8 encoding
+/// of the typed string, since a character may be split across chunks.
+#[doc(hidden)]
+pub trait __Utf8 {
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn __push_utf8(bytes: &mut Vec<u8>);
+}
+
+impl __Utf8 for __tuplez::Unit {
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn __push_utf8(_: &mut Vec<u8>) {}
+}
+
+impl<const C: char, Other> __Utf8 for __tuplez::Tuple<Character<C>, Other>
+where
+    Other: __Utf8,
+{
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn __push_utf8(bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(C.encode_utf8(&mut [0; 4]).as_bytes());
+        Other::__push_utf8(bytes);
+    }
+}
+
+impl<const BYTES: u128, const LEN: u8, Other> __Utf8 for __tuplez::Tuple<Chunk<BYTES, LEN>, Other>
+where
+    Other: __Utf8,
+{
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn __push_utf8(bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&BYTES.to_be_bytes()[..LEN as usize]);
+        Other::__push_utf8(bytes);
+    }
+}
+
+impl<Tag, S> __Utf8 for Tagged<Tag, S>
+where
+    S: __Utf8,
+{
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn __push_utf8(bytes: &mut Vec<u8>) {
+        S::__push_utf8(bytes);
+    }
+}
+
 /// Get original string from typed string (requires `alloc` or `std` feature).
 pub trait TypedString {
     /// The original string.
@@ -131,6 +290,194 @@ where
     }
 }
 
+impl<const BYTES: u128, const LEN: u8, Other> TypedString
+    for __tuplez::Tuple<Chunk<BYTES, LEN>, Other>
+where
+    Other: __Utf8,
+{
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn value() -> String {
+        let mut bytes = Vec::new();
+        <Self as __Utf8>::__push_utf8(&mut bytes);
+        String::from_utf8(bytes).expect("typed string is not valid UTF-8")
+    }
+}
+
+impl<Tag, S> TypedString for Tagged<Tag, S>
+where
+    S: TypedString,
+{
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn value() -> String {
+        S::value()
+    }
+}
+
+/// Compare two typed strings in constant evaluation, see [`StrCmp`].
+///
+/// # Example
+///
+/// ```
+/// use stringz::*;
+///
+/// assert!(<ident!(hello) as StrEq<string!("hello")>>::EQ);
+/// assert!(!<compact_ident!(hello) as StrEq<compact_ident!(world)>>::EQ);
+/// ```
+pub trait StrEq<Other> {
+    /// Whether the two typed strings are equal.
+    const EQ: bool;
+}
+
+impl<T, Other> StrEq<Other> for T
+where
+    T: StrCmp<Other>,
+{
+    const EQ: bool = matches!(T::CMP, Ordering::Equal);
+}
+
+/// Compare two typed strings lexicographically in constant evaluation, which is the same order
+/// as comparing their original strings.
+///
+/// The result is an associated constant rather than a type, because stable Rust cannot
+/// select types by the values of `const` parameters.
+///
+/// Typed strings of different [encodings](crate#compact-encoding) can only be compared
+/// if their first bytes differ, and compact typed strings chunked differently (by [`concatstr`])
+/// can only be compared if they differ within the shorter chunk. Otherwise the compilation fails.
+///
+/// # Example
+///
+/// ```
+/// use core::cmp::Ordering;
+/// use stringz::*;
+///
+/// const CMP: Ordering = <compact_ident!(apple) as StrCmp<compact_ident!(banana)>>::CMP;
+/// assert_eq!(CMP, Ordering::Less);
+/// assert_eq!(<string!("ab") as StrCmp<string!("a")>>::CMP, Ordering::Greater);
+/// ```
+pub trait StrCmp<Other> {
+    /// The ordering of the two typed strings.
+    const CMP: Ordering;
+}
+
+impl StrCmp<__tuplez::Unit> for __tuplez::Unit {
+    const CMP: Ordering = Ordering::Equal;
+}
+
+impl<First, Other> StrCmp<__tuplez::Tuple<First, Other>> for __tuplez::Unit {
+    const CMP: Ordering = Ordering::Less;
+}
+
+impl<First, Other> StrCmp<__tuplez::Unit> for __tuplez::Tuple<First, Other> {
+    const CMP: Ordering = Ordering::Greater;
+}
+
+impl<const C1: char, const C2: char, Other1, Other2> StrCmp<__tuplez::Tuple<Character<C2>, Other2>>
+    for __tuplez::Tuple<Character<C1>, Other1>
+where
+    Other1: StrCmp<Other2>,
+{
+    const CMP: Ordering = if (C1 as u32) < (C2 as u32) {
+        Ordering::Less
+    } else if (C1 as u32) > (C2 as u32) {
+        Ordering::Greater
+    } else {
+        Other1::CMP
+    };
+}
+
+impl<const B1: u128, const L1: u8, const B2: u128, const L2: u8, Other1, Other2>
+    StrCmp<__tuplez::Tuple<Chunk<B2, L2>, Other2>> for __tuplez::Tuple<Chunk<B1, L1>, Other1>
+where
+    Other1: StrCmp<Other2> + StrCmp<__tuplez::Unit>,
+    Other2: StrCmp<__tuplez::Unit>,
+{
+    const CMP: Ordering = {
+        let len = if L1 < L2 { L1 } else { L2 };
+        let mask = if len == 0 {
+            0
+        } else {
+            u128::MAX << (128 - 8 * len as u32)
+        };
+        if B1 & mask < B2 & mask {
+            Ordering::Less
+        } else if B1 & mask > B2 & mask {
+            Ordering::Greater
+        } else if L1 == L2 {
+            <Other1 as StrCmp<Other2>>::CMP
+        } else if L1 < L2 && is_empty(<Other1 as StrCmp<__tuplez::Unit>>::CMP) {
+            Ordering::Less
+        } else if L1 > L2 && is_empty(<Other2 as StrCmp<__tuplez::Unit>>::CMP) {
+            Ordering::Greater
+        } else {
+            panic!("cannot compare compact typed strings chunked differently")
+        }
+    };
+}
+
+impl<const C: char, const B: u128, const L: u8, Other1, Other2>
+    StrCmp<__tuplez::Tuple<Chunk<B, L>, Other2>> for __tuplez::Tuple<Character<C>, Other1>
+{
+    const CMP: Ordering = first_byte_cmp(first_utf8_byte(C), (B >> 120) as u8);
+}
+
+impl<const C: char, const B: u128, const L: u8, Other1, Other2>
+    StrCmp<__tuplez::Tuple<Character<C>, Other2>> for __tuplez::Tuple<Chunk<B, L>, Other1>
+{
+    const CMP: Ordering = first_byte_cmp((B >> 120) as u8, first_utf8_byte(C));
+}
+
+impl<Tag, S, Other> StrCmp<Other> for Tagged<Tag, S>
+where
+    S: StrCmp<Other>,
+{
+    const CMP: Ordering = S::CMP;
+}
+
+impl<Tag, S> StrCmp<Tagged<Tag, S>> for __tuplez::Unit
+where
+    __tuplez::Unit: StrCmp<S>,
+{
+    const CMP: Ordering = <__tuplez::Unit as StrCmp<S>>::CMP;
+}
+
+impl<First, Other, Tag, S> StrCmp<Tagged<Tag, S>> for __tuplez::Tuple<First, Other>
+where
+    __tuplez::Tuple<First, Other>: StrCmp<S>,
+{
+    const CMP: Ordering = <__tuplez::Tuple<First, Other> as StrCmp<S>>::CMP;
+}
+
+/// Whether the result of comparing a typed string with the empty one means it is empty.
+const fn is_empty(cmp: Ordering) -> bool {
+    matches!(cmp, Ordering::Equal)
+}
+
+/// The first byte of the UTF-8 encoding of a character.
+const fn first_utf8_byte(c: char) -> u8 {
+    let c = c as u32;
+    if c < 0x80 {
+        c as u8
+    } else if c < 0x800 {
+        (0xC0 | (c >> 6)) as u8
+    } else if c < 0x10000 {
+        (0xE0 | (c >> 12)) as u8
+    } else {
+        (0xF0 | (c >> 18)) as u8
+    }
+}
+
+/// Compare typed strings of different encodings by their first bytes.
+const fn first_byte_cmp(b1: u8, b2: u8) -> Ordering {
+    if b1 < b2 {
+        Ordering::Less
+    } else if b1 > b2 {
+        Ordering::Greater
+    } else {
+        panic!("cannot compare typed strings of different encodings with the same first byte")
+    }
+}
+
 /// Concatenate multiple typed strings.
 ///
 /// # Example
@@ -147,6 +494,16 @@ where
 ///     FullQualified::default()
 /// }
 /// ```
+///
+/// Typed strings in the [compact encoding](crate#compact-encoding) can be concatenated too,
+/// but the result is only equal in value, not in type, to the compact typed string of the whole:
+///
+/// ```
+/// use stringz::*;
+///
+/// type Path = concatstr!(compact_ident!(my_crate), string!("::"), compact_ident!(Amazing));
+/// assert_eq!(Path::value(), "my_crate::Amazing");
+/// ```
 #[macro_export]
 macro_rules! concatstr {
     ($t:ty) => {
@@ -1,57 +1,405 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, parse_quote, punctuated::Punctuated};
+use syn::{
+    ext::IdentExt, parse_macro_input, parse_quote, punctuated::Punctuated, visit_mut::VisitMut,
+};
 
+mod lifetime;
 mod parse;
 
 use parse::*;
 
+/// The typed field name, the typed names of optional fields are tagged as optional.
+fn field_type(path: &syn::Path, name: &FieldName, optional: bool) -> syn::Type {
+    let name = name.to_type(path);
+    if optional {
+        parse_quote! { #path::__Optional<#name> }
+    } else {
+        name
+    }
+}
+
+/// The type of data carried by the field, optional fields carry an `Option`.
+fn value_type(
+    path: &syn::Path,
+    ty: &Nested<syn::Type>,
+    optional: bool,
+    declare: bool,
+) -> proc_macro2::TokenStream {
+    let ty = match nested_fields(ty) {
+        Some(AnonymousStructType(fields)) => {
+            struct_type(path, &fields.iter().collect::<Vec<_>>(), declare)
+        }
+        None => ty.expand(path, "stru_t"),
+    };
+    if optional {
+        quote! { ::core::option::Option<#ty> }
+    } else {
+        ty
+    }
+}
+
+/// The error reported when a field is defined more than once.
+fn duplicate_error(span: proc_macro2::Span) -> proc_macro2::TokenStream {
+    syn::Error::new(span, "field already defined").to_compile_error()
+}
+
+/// Conditional fields that fail the compilation if fields with the same name are compiled in together.
+fn duplicate_guards<T>(
+    fields: &[T],
+    name: impl Fn(&T) -> (String, proc_macro2::Span),
+    cfg: impl Fn(&T) -> Option<&syn::Meta>,
+) -> Result<Vec<(Option<syn::Meta>, proc_macro2::TokenStream)>, proc_macro2::TokenStream> {
+    find_duplicates(fields, name, cfg)
+        .into_iter()
+        .map(|(cfg, span)| match cfg {
+            Some(cfg) => Ok((Some(cfg), duplicate_error(span))),
+            None => Err(duplicate_error(span)),
+        })
+        .collect()
+}
+
+/// The anonymous struct type of the fields, conditional fields are prepended by `__CfgField`
+/// so that each of them is compiled in or out on its own.
+fn cfg_tuple_type(
+    path: &syn::Path,
+    fields: Vec<(Option<syn::Meta>, proc_macro2::TokenStream)>,
+) -> proc_macro2::TokenStream {
+    fields
+        .into_iter()
+        .rev()
+        .fold(quote! { #path::__tuplez::Unit }, |other, (cfg, field)| match cfg {
+            Some(cfg) => quote! { <fn(#[cfg(#cfg)] #field) as #path::__CfgField<#other>>::Output },
+            None => quote! { #path::__tuplez::Tuple<#field, #other> },
+        })
+}
+
+/// The anonymous struct object of the fields, conditional fields are prepended by `__CfgValue`
+/// so that each of them is compiled in or out on its own.
+fn cfg_tuple(
+    path: &syn::Path,
+    fields: Vec<(Option<syn::Meta>, proc_macro2::TokenStream)>,
+) -> proc_macro2::TokenStream {
+    fields
+        .into_iter()
+        .rev()
+        .fold(quote! { #path::__tuplez::Unit }, |other, (cfg, field)| match cfg {
+            Some(cfg) => quote! { #path::__CfgValue::insert((#[cfg(#cfg)] #field,), #other) },
+            None => quote! { #path::__tuplez::Tuple(#field, #other) },
+        })
+}
+
 #[proc_macro]
 pub fn stru(input: TokenStream) -> TokenStream {
     let ReExportStructz {
         path,
-        other: AnonymousStruct(input),
+        other: AnonymousStruct {
+            fields,
+            fill_optional,
+        },
     } = parse_macro_input!(input as ReExportStructz<AnonymousStruct>);
-    let fields: Vec<_> = input
-        .into_iter()
-        .map(|(ident, expr)| match expr {
-            Some(expr) => quote! { (<#path::ident!(#ident)>::default(), #expr) },
-            None => quote! { (<#path::ident!(#ident)>::default(), #ident) },
-        })
-        .collect();
+    let guards = match duplicate_guards(
+        &fields,
+        |field| (field.name.value(), field.name.span()),
+        |field| field.cfg.as_ref(),
+    ) {
+        Ok(guards) => guards,
+        Err(e) => return e.into(),
+    };
+    let values = fields.iter().map(|field| {
+        let name = field_type(&path, &field.name, field.optional);
+        let expr = field.expr.expand(&path, "stru");
+        (field.cfg.clone(), quote! { (<#name>::default(), #expr) })
+    });
+    let expr = cfg_tuple(&path, guards.into_iter().chain(values).collect());
+    if fill_optional {
+        quote! {
+            <_ as #path::FillOptional<_, _>>::fill_optional(#expr)
+        }
+        .into()
+    } else {
+        expr.into()
+    }
+}
+
+/// The fields of the nested anonymous struct type, if the type is brace-delimited.
+fn nested_fields(ty: &Nested<syn::Type>) -> Option<AnonymousStructType> {
+    match ty {
+        Nested::Plain(_) => None,
+        Nested::Braced(tokens) => syn::parse2(tokens.clone()).ok(),
+    }
+}
+
+/// The first default value declared by the fields, or by the fields of the nested anonymous struct types.
+fn first_default(fields: &[&FieldType]) -> Option<proc_macro2::Span> {
+    fields.iter().find_map(|field| match &field.default {
+        Some(expr) => Some(syn::spanned::Spanned::span(expr)),
+        None => nested_fields(&field.ty).and_then(|AnonymousStructType(fields)| {
+            first_default(&fields.iter().collect::<Vec<_>>())
+        }),
+    })
+}
+
+/// The anonymous struct type of the fields, which also implements `DeclaredDefaults`
+/// if `declare` is set and any field, or any field of the nested anonymous struct types,
+/// declares a default value.
+fn struct_type(path: &syn::Path, fields: &[&FieldType], declare: bool) -> proc_macro2::TokenStream {
+    if let Some(field) = fields
+        .iter()
+        .find(|field| field.cfg.is_some() && has_elided_lifetime(&field.ty))
+    {
+        return syn::Error::new(
+            field.name.span(),
+            "lifetimes of conditional fields must be named",
+        )
+        .to_compile_error();
+    }
+    let guards = match duplicate_guards(
+        fields,
+        |field| (field.name.value(), field.name.span()),
+        |field| field.cfg.as_ref(),
+    ) {
+        Ok(guards) => guards,
+        Err(e) => return e,
+    };
+    let types = fields.iter().map(|field| {
+        let name = field_type(path, &field.name, field.optional);
+        let ty = value_type(path, &field.ty, field.optional, declare);
+        (field.cfg.clone(), quote! { (#name, #ty) })
+    });
+    let ty = cfg_tuple_type(path, guards.into_iter().chain(types).collect());
+    if !declare || first_default(fields).is_none() {
+        return ty;
+    }
+    let plain = struct_type(path, fields, false);
+    let values = default_values(path, fields);
     quote! {
-        #path::__tuplez::tuple!(#(#fields),*)
+        <#ty as #path::__WithItems<{
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            pub struct Declaration;
+
+            impl #path::DeclaredDefaults<Declaration> for #plain {
+                fn defaults() -> Self {
+                    #values
+                }
+            }
+
+            0
+        }>>::Output
+    }
+}
+
+/// The anonymous struct object of which all fields take their default values, either declared
+/// or `Default::default()`.
+fn default_values(path: &syn::Path, fields: &[&FieldType]) -> proc_macro2::TokenStream {
+    let values = fields.iter().map(|field| {
+        let name = field_type(path, &field.name, field.optional);
+        let value = match (&field.default, nested_fields(&field.ty)) {
+            (Some(expr), _) => quote! { #expr },
+            (None, Some(AnonymousStructType(fields))) if !field.optional => {
+                default_values(path, &fields.iter().collect::<Vec<_>>())
+            }
+            (None, _) => quote! { ::core::default::Default::default() },
+        };
+        (field.cfg.clone(), quote! { (<#name>::default(), #value) })
+    });
+    cfg_tuple(path, values.collect())
+}
+
+/// Whether the type of the field, or the types of the fields of the nested anonymous struct,
+/// contains elided lifetimes.
+fn has_elided_lifetime(ty: &Nested<syn::Type>) -> bool {
+    match ty {
+        Nested::Plain(ty) => lifetime::has_elided(ty),
+        Nested::Braced(_) => nested_fields(ty).is_some_and(|AnonymousStructType(fields)| {
+            fields.iter().any(|field| has_elided_lifetime(&field.ty))
+        }),
     }
-    .into()
 }
 
 #[proc_macro]
 pub fn stru_t(input: TokenStream) -> TokenStream {
+    let ReExportStructz { path, other: input } =
+        parse_macro_input!(input as ReExportStructz<AnonymousStructTypeInput>);
+    match input {
+        AnonymousStructTypeInput::Type(AnonymousStructType(input)) => {
+            struct_type(&path, &input.iter().collect::<Vec<_>>(), true).into()
+        }
+        AnonymousStructTypeInput::Alias(AnonymousStructTypeAlias {
+            attrs,
+            vis,
+            ident: alias,
+            fields: AnonymousStructType(input),
+        }) => {
+            let ty = struct_type(&path, &input.iter().collect::<Vec<_>>(), true);
+            quote! {
+                #(#attrs)*
+                #vis type #alias = #ty;
+            }
+            .into()
+        }
+    }
+}
+
+#[proc_macro]
+pub fn field_access(input: TokenStream) -> TokenStream {
     let ReExportStructz {
         path,
-        other: AnonymousStructType(input),
-    } = parse_macro_input!(input as ReExportStructz<AnonymousStructType>);
-    let fields: Vec<_> = input
-        .into_iter()
-        .map(|(ident, ty)| quote! { (#path::ident!(#ident), #ty) })
-        .collect();
-    quote! {
-        #path::__tuplez::tuple_t!(#(#fields),*)
+        other: FieldAccess {
+            op,
+            object,
+            name,
+            args,
+        },
+    } = parse_macro_input!(input as ReExportStructz<FieldAccess>);
+    let field = name.to_type(&path);
+    // The name is spelled out in the diagnostic of a trait declared for this access,
+    // so that it is reported as it is written rather than as a typed field name.
+    let escaped = name.value().replace('{', "{{").replace('}', "}}");
+    let message = format!("anonymous struct has no field `{escaped}`");
+    let label = format!("unknown field `{escaped}`");
+    let arity = match op.to_string().as_str() {
+        "take" | "take_rest" | "get" | "get_mut" => 0,
+        "replace" | "map" => 1,
+        _ => {
+            return syn::Error::new(op.span(), "unknown field access")
+                .to_compile_error()
+                .into()
+        }
+    };
+    if args.len() != arity {
+        return syn::Error::new(op.span(), format!("expected {arity} arguments"))
+            .to_compile_error()
+            .into();
     }
+    let args = &args;
+    // The access is done on the position of the field, which is only inferred by the bound of
+    // the trait declared here, so that a missing field is reported for this trait alone.
+    let (bound, ops) = match op.to_string().as_str() {
+        "replace" | "map" => (quote! { __FieldUpdate }, quote! { __FieldUpdateOps }),
+        _ => (quote! { __FieldAccess }, quote! { __FieldOps }),
+    };
+    let access = match op.to_string().as_str() {
+        "take" => quote! {
+            fn __access<S: __Field<R>, R: #path::#ops<S, #field>>(s: S) -> R::Value {
+                R::__take_field(s)
+            }
+        },
+        "take_rest" => quote! {
+            fn __access<S: __Field<R>, R: #path::#ops<S, #field>>(s: S) -> (R::Value, R::Rest) {
+                R::__take_field_rest(s)
+            }
+        },
+        "get" => quote! {
+            fn __access<'a, S: __Field<R>, R: 'a + #path::#ops<S, #field>>(s: &'a S) -> R::Ref<'a> {
+                R::__get_field(s)
+            }
+        },
+        "get_mut" => quote! {
+            fn __access<'a, S: __Field<R>, R: 'a + #path::#ops<S, #field>>(s: &'a mut S) -> R::Mut<'a> {
+                R::__get_field_mut(s)
+            }
+        },
+        "replace" => quote! {
+            fn __access<S: __Field<R>, R: #path::#ops<S, #field>>(s: &mut S, value: R::Value) -> R::Value {
+                R::__replace_field(s, value)
+            }
+        },
+        _ => quote! {
+            fn __access<S: __Field<R>, R: #path::#ops<S, #field>, U, F>(s: S, f: F) -> R::MapOutput<U>
+            where
+                F: FnOnce(R::Value) -> R::MapValue<U>,
+            {
+                R::__map_field(s, f)
+            }
+        },
+    };
+    // The struct is passed through a method call, so that its type is not known until the bound
+    // is checked, which reports a missing field only once. References to structs are
+    // dereferenced by the method call when borrowing.
+    let receiver = match op.to_string().as_str() {
+        "take" | "take_rest" | "map" => quote! { #object.__own() },
+        "get" => quote! { #object.__borrow() },
+        _ => quote! { #object.__borrow_mut() },
+    };
+    quote! {{
+        #[diagnostic::on_unimplemented(
+            message = #message,
+            label = #label,
+            note = "field names are case-sensitive, and optional fields are accessed by their plain names"
+        )]
+        trait __Field<R>: #path::#bound<#field, R> {}
+
+        impl<S, R> __Field<R> for S where S: #path::#bound<#field, R> {}
+
+        #access
+
+        trait __Receiver {
+            fn __own(self) -> Self
+            where
+                Self: Sized,
+            {
+                self
+            }
+
+            fn __borrow(&self) -> &Self {
+                self
+            }
+
+            fn __borrow_mut(&mut self) -> &mut Self {
+                self
+            }
+        }
+
+        impl<S: ?Sized> __Receiver for S {}
+
+        __access(#receiver #(, #args)*)
+    }}
     .into()
 }
 
+#[proc_macro]
+pub fn enu_t(input: TokenStream) -> TokenStream {
+    let ReExportStructz {
+        path,
+        other: AnonymousEnumType(variants),
+    } = parse_macro_input!(input as ReExportStructz<AnonymousEnumType>);
+    variants
+        .iter()
+        .rev()
+        .fold(
+            quote! { #path::Never },
+            |other, VariantType { name, ty }| {
+                let name = name.to_type(&path);
+                quote! { #path::Choice<#name, #ty, #other> }
+            },
+        )
+        .into()
+}
+
 #[proc_macro_attribute]
 pub fn named_args(_: TokenStream, item: TokenStream) -> TokenStream {
     let mut input = parse_macro_input!(item as syn::ItemFn);
     let mut args = vec![];
     let mut has_self = None;
-    for arg in input.sig.inputs {
+    let mut elided = lifetime::NameElided::new();
+    for arg in &input.sig.inputs {
         match arg {
-            syn::FnArg::Receiver(arg) => has_self = Some(arg),
+            syn::FnArg::Receiver(arg) => has_self = Some(arg.clone()),
             syn::FnArg::Typed(arg) => {
-                if let syn::Pat::Ident(pat) = *arg.pat {
-                    args.push((pat, arg.ty))
+                let cfg = match parse_cfg(&arg.attrs) {
+                    Ok(cfg) => cfg,
+                    Err(e) => return e.to_compile_error().into(),
+                };
+                let mut ty = arg.ty.clone();
+                if cfg.is_some() {
+                    // Conditional arguments are wrapped in function pointer types,
+                    // where elided lifetimes would become higher-ranked.
+                    elided.visit_type_mut(&mut ty);
+                }
+                if let syn::Pat::Ident(pat) = &*arg.pat {
+                    args.push((cfg, pat.ident.clone(), ty))
                 } else {
                     return quote! {
                        compile_error!("arguments must be an identifier or receiver");
@@ -61,10 +409,23 @@ pub fn named_args(_: TokenStream, item: TokenStream) -> TokenStream {
             }
         }
     }
-    args.sort_by(|x, y| x.0.ident.cmp(&y.0.ident));
-    let (idents, tys): (Vec<syn::PatIdent>, Vec<Box<syn::Type>>) = args.into_iter().unzip();
+    args.sort_by_cached_key(|(_, ident, _)| ident.unraw().to_string());
+    for lifetime in elided.lifetimes.into_iter().rev() {
+        input
+            .sig
+            .generics
+            .params
+            .insert(0, syn::GenericParam::Lifetime(syn::LifetimeParam::new(lifetime)));
+    }
+    for ty in elided.types {
+        input.sig.generics.params.push(syn::GenericParam::Type(ty));
+    }
+    let fields = args.iter().map(|(cfg, ident, ty)| {
+        let cfg = cfg.as_ref().map(|cfg| quote! { #[cfg(#cfg)] });
+        quote! { #cfg #ident: #ty }
+    });
     let struct_type: syn::Type = parse_quote! {
-        ::structz::stru_t! { #(#idents: #tys),* }
+        ::structz::stru_t! { #(#fields),* }
     };
     let mut inputs = Punctuated::new();
     if let Some(arg) = has_self {
@@ -77,19 +438,18 @@ pub fn named_args(_: TokenStream, item: TokenStream) -> TokenStream {
         ty: Box::new(struct_type),
     }));
     input.sig.inputs = inputs;
-    let mut unpack: Vec<(syn::Pat, syn::Type)> = vec![];
-    for ident in idents {
-        let ident = ident.ident;
-        unpack.push((
-            parse_quote! { #ident },
-            parse_quote! { ::structz::ident!(#ident) },
-        ))
-    }
-    let (pat, ident_tys): (Vec<syn::Pat>, Vec<syn::Type>) = unpack.into_iter().unzip();
-    let pat: syn::Pat = parse_quote! { ::structz::__tuplez::tuple_pat!( #( (_, #pat) ),* )};
-    let ident_tys: syn::Type =
-        parse_quote! { ::structz::__tuplez::tuple_t!( #( (#ident_tys, #tys) ),* )};
-    let unpack: syn::Stmt = parse_quote! { let #pat: #ident_tys = structz_s; };
-    input.block.stmts.insert(0, unpack);
-    quote!(#input).into()
+    // Unpack the fields one by one in order, so that each conditional field is unpacked on its own.
+    let mut unpack: Vec<syn::Stmt> = args
+        .iter()
+        .map(|(cfg, ident, _)| {
+            let cfg = cfg.as_ref().map(|cfg| quote! { #[cfg(#cfg)] });
+            parse_quote! {
+                #cfg
+                let ::structz::__tuplez::Tuple((_, #ident), structz_s) = structz_s;
+            }
+        })
+        .collect();
+    unpack.push(parse_quote! { let _ = structz_s; });
+    input.block.stmts.splice(0..0, unpack);
+    quote! { #input }.into()
 }
@@ -0,0 +1,97 @@
+use syn::{
+    parse_quote,
+    visit::{self, Visit},
+    visit_mut::{self, VisitMut},
+};
+
+/// Find elided lifetimes in a type, which become higher-ranked if the type is wrapped in
+/// a function pointer type to be compiled in or out by `#[cfg]`.
+struct FindElided(bool);
+
+impl Visit<'_> for FindElided {
+    fn visit_type_reference(&mut self, ty: &syn::TypeReference) {
+        if ty.lifetime.is_none() {
+            self.0 = true;
+        }
+        visit::visit_type_reference(self, ty);
+    }
+
+    fn visit_lifetime(&mut self, lifetime: &syn::Lifetime) {
+        if lifetime.ident == "_" {
+            self.0 = true;
+        }
+    }
+
+    // Function pointers and `Fn` traits have their own scopes of elided lifetimes.
+    fn visit_type_bare_fn(&mut self, _: &syn::TypeBareFn) {}
+
+    fn visit_parenthesized_generic_arguments(&mut self, _: &syn::ParenthesizedGenericArguments) {}
+}
+
+/// Whether the type contains elided lifetimes.
+pub fn has_elided(ty: &syn::Type) -> bool {
+    let mut find = FindElided(false);
+    find.visit_type(ty);
+    find.0
+}
+
+/// Replace elided lifetimes and `impl Trait` in types of function arguments with new generic
+/// parameters of the function.
+pub struct NameElided {
+    pub lifetimes: Vec<syn::Lifetime>,
+    pub types: Vec<syn::TypeParam>,
+}
+
+impl NameElided {
+    pub fn new() -> Self {
+        Self {
+            lifetimes: vec![],
+            types: vec![],
+        }
+    }
+
+    fn lifetime(&mut self) -> syn::Lifetime {
+        let lifetime = syn::Lifetime::new(
+            &format!("'__structz{}", self.lifetimes.len()),
+            proc_macro2::Span::call_site(),
+        );
+        self.lifetimes.push(lifetime.clone());
+        lifetime
+    }
+}
+
+impl VisitMut for NameElided {
+    fn visit_type_mut(&mut self, ty: &mut syn::Type) {
+        visit_mut::visit_type_mut(self, ty);
+        if let syn::Type::ImplTrait(syn::TypeImplTrait { bounds, .. }) = ty {
+            let ident = syn::Ident::new(
+                &format!("__Structz{}", self.types.len()),
+                proc_macro2::Span::call_site(),
+            );
+            self.types.push(parse_quote! { #ident: #bounds });
+            *ty = parse_quote! { #ident };
+        }
+    }
+
+    fn visit_type_reference_mut(&mut self, ty: &mut syn::TypeReference) {
+        if ty.lifetime.is_none() {
+            ty.lifetime = Some(self.lifetime());
+        }
+        visit_mut::visit_type_reference_mut(self, ty);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.lifetime();
+        }
+    }
+
+    // Function pointers and `Fn` traits have their own scopes of elided lifetimes.
+    fn visit_type_bare_fn_mut(&mut self, _: &mut syn::TypeBareFn) {}
+
+    fn visit_parenthesized_generic_arguments_mut(
+        &mut self,
+        _: &mut syn::ParenthesizedGenericArguments,
+    ) {
+    }
+}
@@ -1,4 +1,5 @@
-use syn::{parse::Parse, Expr, Ident, Token, Type};
+use quote::ToTokens;
+use syn::{ext::IdentExt, parse::Parse, parse_quote, Attribute, Expr, Ident, Meta, Token, Type};
 
 pub struct ReExportStructz<T: syn::parse::Parse> {
     pub path: syn::Path,
@@ -14,69 +15,444 @@ impl<T: syn::parse::Parse> syn::parse::Parse for ReExportStructz<T> {
     }
 }
 
+/// The name of a field, either an identifier or a string literal.
+#[derive(Clone)]
+pub enum FieldName {
+    Ident(Ident),
+    Str(syn::LitStr),
+}
+
+impl FieldName {
+    /// The string value of the name.
+    pub fn value(&self) -> String {
+        match self {
+            Self::Ident(ident) => ident.unraw().to_string(),
+            Self::Str(lit) => lit.value(),
+        }
+    }
+
+    pub fn span(&self) -> proc_macro2::Span {
+        match self {
+            Self::Ident(ident) => ident.span(),
+            Self::Str(lit) => lit.span(),
+        }
+    }
+
+    /// The typed field name.
+    pub fn to_type(&self, path: &syn::Path) -> syn::Type {
+        match self {
+            Self::Ident(ident) => parse_quote! { #path::ident!(#ident) },
+            Self::Str(lit) => parse_quote! { #path::__stringz::compact_string!(#lit) },
+        }
+    }
+}
+
+impl Parse for FieldName {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.peek(syn::LitStr) {
+            let lit: syn::LitStr = input.parse()?;
+            let value = lit.value();
+            if value.is_empty() {
+                return Err(syn::Error::new(lit.span(), "field name cannot be empty"));
+            }
+            Ok(Self::Str(lit))
+        } else {
+            input.parse().map(Self::Ident)
+        }
+    }
+}
+
+/// Sort key of a field, optional fields are sorted after the required fields of the same name.
+fn field_key(name: &FieldName, optional: bool) -> (String, bool) {
+    (name.value(), optional)
+}
+
+/// Collect the predicates of `#[cfg]` attributes into one, other attributes are rejected.
+pub fn parse_cfg(attrs: &[Attribute]) -> syn::Result<Option<Meta>> {
+    let mut predicates = Vec::new();
+    for attr in attrs {
+        if !attr.path().is_ident("cfg") {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "only `#[cfg]` attributes are supported on fields",
+            ));
+        }
+        predicates.push(attr.parse_args::<Meta>()?);
+    }
+    Ok(match predicates.len() {
+        0 => None,
+        1 => predicates.pop(),
+        _ => Some(parse_quote! { all(#(#predicates),*) }),
+    })
+}
+
+/// Find the fields defined more than once, returns the predicate under which both fields of a pair
+/// are compiled in, and the span of the latter one.
+///
+/// The predicate is `None` if neither field has `#[cfg]` attributes.
+pub fn find_duplicates<T>(
+    fields: &[T],
+    name: impl Fn(&T) -> (String, proc_macro2::Span),
+    cfg: impl Fn(&T) -> Option<&Meta>,
+) -> Vec<(Option<Meta>, proc_macro2::Span)> {
+    let mut duplicates = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let (key, span) = name(field);
+        for other in &fields[..i] {
+            if name(other).0 != key {
+                continue;
+            }
+            let predicate = match (cfg(other), cfg(field)) {
+                (Some(p1), Some(p2)) => Some(parse_quote! { all(#p1, #p2) }),
+                (Some(p), None) | (None, Some(p)) => Some(p.clone()),
+                (None, None) => None,
+            };
+            duplicates.push((predicate, span));
+        }
+    }
+    duplicates
+}
+
+/// The value or type of a field, brace-delimited ones are nested anonymous structs.
+pub enum Nested<T> {
+    Plain(T),
+    Braced(proc_macro2::TokenStream),
+}
+
+/// Access to a field: the operation, the struct, the name and the other arguments of the operation.
+pub struct FieldAccess {
+    pub op: Ident,
+    pub object: Ident,
+    pub name: FieldName,
+    pub args: Vec<Expr>,
+}
+
+impl Parse for FieldAccess {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let op = input.parse()?;
+        let _: Token![;] = input.parse()?;
+        let object = input.parse()?;
+        let _: Token![;] = input.parse()?;
+        let name = input.parse()?;
+        let mut args = Vec::new();
+        if input.parse::<Option<Token![;]>>()?.is_some() {
+            args = input
+                .parse_terminated(Expr::parse, Token![,])?
+                .into_iter()
+                .collect();
+        }
+        Ok(Self {
+            op,
+            object,
+            name,
+            args,
+        })
+    }
+}
+
+impl<T: Parse> Parse for Nested<T> {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.peek(syn::token::Brace) {
+            let content;
+            syn::braced!(content in input);
+            Ok(Self::Braced(content.parse()?))
+        } else {
+            input.parse().map(Self::Plain)
+        }
+    }
+}
+
+impl<T: ToTokens> Nested<T> {
+    /// Expand the nested anonymous struct by the macro `name` of structz.
+    pub fn expand(&self, path: &syn::Path, name: &str) -> proc_macro2::TokenStream {
+        match self {
+            Self::Plain(value) => value.to_token_stream(),
+            Self::Braced(tokens) => {
+                let name = Ident::new(name, proc_macro2::Span::call_site());
+                quote::quote! { #path::#name! { #tokens } }
+            }
+        }
+    }
+}
+
+pub struct FieldValue {
+    pub cfg: Option<Meta>,
+    pub name: FieldName,
+    pub optional: bool,
+    pub expr: Nested<Expr>,
+}
+
+/// How a variable is captured by the shorthand of a field.
+enum Capture {
+    Move,
+    Ref,
+    RefMut,
+    Clone,
+}
 
-pub struct AnonymousStruct(pub Vec<(Ident, Option<Expr>)>);
+/// Parse the shorthand of a field: an optional capture modifier (`ref`, `ref mut` or `clone`),
+/// followed by a variable or a path of fields (e.g. `person.age`, `self.id`),
+/// whose last segment is used as the field name.
+///
+/// Returns the field name, the expression, and whether the shorthand is a bare variable.
+fn parse_shorthand(input: syn::parse::ParseStream) -> syn::Result<(Ident, Expr, bool)> {
+    let capture = if input.peek(Token![ref]) {
+        let _: Token![ref] = input.parse()?;
+        if input.peek(Token![mut]) {
+            let _: Token![mut] = input.parse()?;
+            Capture::RefMut
+        } else {
+            Capture::Ref
+        }
+    } else if input.peek(Ident) && (input.peek2(Ident) || input.peek2(Token![self])) {
+        let modifier: Ident = input.parse()?;
+        if modifier != "clone" {
+            return Err(syn::Error::new(
+                modifier.span(),
+                "expected `ref`, `ref mut` or `clone`",
+            ));
+        }
+        Capture::Clone
+    } else {
+        Capture::Move
+    };
+    let lookahead = input.lookahead1();
+    let (mut place, mut name): (Expr, _) = if lookahead.peek(Token![self]) {
+        let token: Token![self] = input.parse()?;
+        (parse_quote! { #token }, None)
+    } else if lookahead.peek(Ident) {
+        let ident: Ident = input.parse()?;
+        (parse_quote! { #ident }, Some(ident))
+    } else {
+        return Err(lookahead.error());
+    };
+    let bare = name.is_some() && matches!(capture, Capture::Move);
+    let mut is_path = false;
+    while input.peek(Token![.]) {
+        let _: Token![.] = input.parse()?;
+        let member: syn::Member = input.parse()?;
+        name = match &member {
+            syn::Member::Named(ident) => Some(ident.clone()),
+            syn::Member::Unnamed(_) => None,
+        };
+        place = parse_quote! { #place.#member };
+        is_path = true;
+    }
+    let Some(name) = name else {
+        return Err(syn::Error::new_spanned(
+            place,
+            "the shorthand of a field must end with a named field or a variable",
+        ));
+    };
+    let expr = match capture {
+        Capture::Move => place,
+        Capture::Ref => parse_quote! { &#place },
+        Capture::RefMut => parse_quote! { &mut #place },
+        Capture::Clone => parse_quote! { ::core::clone::Clone::clone(&#place) },
+    };
+    Ok((name, expr, bare && !is_path))
+}
+
+pub struct AnonymousStruct {
+    pub fields: Vec<FieldValue>,
+    pub fill_optional: bool,
+}
 
 impl Parse for AnonymousStruct {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let mut fields = Vec::new();
+        let mut fields: Vec<FieldValue> = Vec::new();
+        let mut fill_optional = false;
         loop {
             if input.is_empty() {
                 break;
             }
-            let ident: Ident = input.parse()?;
-            if fields.iter().any(|(i, _)| i == &ident) {
-                return Err(syn::Error::new(ident.span(), "field already defined"));
-            }
-            if input.is_empty() {
-                fields.push((ident, None));
+            if input.peek(Token![..]) {
+                let _: Token![..] = input.parse()?;
+                fill_optional = true;
                 break;
             }
-            let lookahead = input.lookahead1();
-            if lookahead.peek(Token![,]) {
-                let _: Token![,] = input.parse()?;
-                fields.push((ident, None));
-                continue;
+            let cfg = parse_cfg(&input.call(Attribute::parse_outer)?)?;
+            let (name, expr, bare) = if input.peek(syn::LitStr) {
+                (input.parse()?, None, true)
+            } else {
+                let (ident, expr, bare) = parse_shorthand(input)?;
+                (FieldName::Ident(ident), Some(expr), bare)
+            };
+            if cfg.is_none()
+                && fields
+                    .iter()
+                    .any(|field| field.cfg.is_none() && field.name.value() == name.value())
+            {
+                return Err(syn::Error::new(name.span(), "field already defined"));
+            }
+            let optional = input.peek(Token![?]);
+            if optional {
+                let _: Token![?] = input.parse()?;
             }
-            if lookahead.peek(Token![:]) {
-                let _: Token![:] = input.parse()?;
-                let expr = input.parse()?;
-                fields.push((ident, Some(expr)));
-                if input.is_empty() {
-                    break;
+            let expr = match expr {
+                Some(expr) if !bare || !input.peek(Token![:]) => Nested::Plain(expr),
+                _ => {
+                    let _: Token![:] = input.parse()?;
+                    input.parse()?
                 }
-                let _: Token![,] = input.parse()?;
+            };
+            fields.push(FieldValue {
+                cfg,
+                name,
+                optional,
+                expr,
+            });
+            if input.is_empty() {
+                break;
             }
+            let _: Token![,] = input.parse()?;
         }
 
-        fields.sort_by(|x, y| x.0.cmp(&y.0));
-        Ok(Self(fields))
+        fields.sort_by_cached_key(|field| field_key(&field.name, field.optional));
+        Ok(Self {
+            fields,
+            fill_optional,
+        })
     }
 }
 
-pub struct AnonymousStructType(pub Vec<(Ident, Type)>);
+pub struct FieldType {
+    pub cfg: Option<Meta>,
+    pub name: FieldName,
+    pub optional: bool,
+    pub ty: Nested<Type>,
+    pub default: Option<Expr>,
+}
+
+pub struct AnonymousStructType(pub Vec<FieldType>);
 
 impl Parse for AnonymousStructType {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let mut fields = Vec::new();
+        let mut fields: Vec<FieldType> = Vec::new();
         loop {
             if input.is_empty() {
                 break;
             }
-            let ident: Ident = input.parse()?;
-            if fields.iter().any(|(i, _)| i == &ident) {
-                return Err(syn::Error::new(ident.span(), "field already defined"));
+            let cfg = parse_cfg(&input.call(Attribute::parse_outer)?)?;
+            let name: FieldName = input.parse()?;
+            if cfg.is_none()
+                && fields
+                    .iter()
+                    .any(|field| field.cfg.is_none() && field.name.value() == name.value())
+            {
+                return Err(syn::Error::new(name.span(), "field already defined"));
+            }
+            let optional = input.peek(Token![?]);
+            if optional {
+                let _: Token![?] = input.parse()?;
             }
             let _: Token![:] = input.parse()?;
             let ty = input.parse()?;
-            fields.push((ident, ty));
+            let default = if input.peek(Token![=]) {
+                let _: Token![=] = input.parse()?;
+                Some(input.parse()?)
+            } else {
+                None
+            };
+            fields.push(FieldType {
+                cfg,
+                name,
+                optional,
+                ty,
+                default,
+            });
             if input.is_empty() {
                 break;
             }
             let _: Token![,] = input.parse()?;
         }
 
-        fields.sort_by(|x, y| x.0.cmp(&y.0));
+        fields.sort_by_cached_key(|field| field_key(&field.name, field.optional));
         Ok(Self(fields))
     }
 }
+
+pub struct VariantType {
+    pub name: FieldName,
+    pub ty: Type,
+}
+
+pub struct AnonymousEnumType(pub Vec<VariantType>);
+
+impl Parse for AnonymousEnumType {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut variants: Vec<VariantType> = Vec::new();
+        loop {
+            if input.is_empty() {
+                break;
+            }
+            let name: FieldName = input.parse()?;
+            if variants
+                .iter()
+                .any(|variant| variant.name.value() == name.value())
+            {
+                return Err(syn::Error::new(name.span(), "variant already defined"));
+            }
+            let _: Token![:] = input.parse()?;
+            let ty = input.parse()?;
+            variants.push(VariantType { name, ty });
+            if input.is_empty() {
+                break;
+            }
+            let _: Token![,] = input.parse()?;
+        }
+
+        variants.sort_by_cached_key(|variant| variant.name.value());
+        Ok(Self(variants))
+    }
+}
+
+pub struct AnonymousStructTypeAlias {
+    pub attrs: Vec<syn::Attribute>,
+    pub vis: syn::Visibility,
+    pub ident: Ident,
+    pub fields: AnonymousStructType,
+}
+
+impl Parse for AnonymousStructTypeAlias {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let _: Token![type] = input.parse()?;
+        let ident = input.parse()?;
+        let _: Token![=] = input.parse()?;
+        let content;
+        syn::braced!(content in input);
+        let fields = content.parse()?;
+        let _: Token![;] = input.parse()?;
+        Ok(Self {
+            attrs,
+            vis,
+            ident,
+            fields,
+        })
+    }
+}
+
+pub enum AnonymousStructTypeInput {
+    Type(AnonymousStructType),
+    Alias(AnonymousStructTypeAlias),
+}
+
+impl Parse for AnonymousStructTypeInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![pub]) || input.peek(Token![type]) {
+            return input.parse().map(Self::Alias);
+        }
+        if input.peek(Token![#]) {
+            // Attributes before `type` belong to the alias, attributes before a field name
+            // belong to the field.
+            let fork = input.fork();
+            fork.call(Attribute::parse_outer)?;
+            if !fork.peek(Ident) || fork.peek(Token![pub]) || fork.peek(Token![type]) {
+                return input.parse().map(Self::Alias);
+            }
+        }
+        input.parse().map(Self::Type)
+    }
+}